@@ -2,6 +2,10 @@
 
 #[macro_use] extern crate rocket;
 
+use iscp::{AlbumArt, IscpError};
+use rocket::http::{ContentType, Status};
+use rocket::response::content::Content;
+use rocket::response::Redirect;
 use rocket::State;
 use std::sync::Mutex;
 use std::time::Duration;
@@ -10,6 +14,12 @@ struct SharedData {
     remote: Mutex<iscp::Remote>,
 }
 
+#[derive(Responder)]
+enum ArtResponse {
+    Image(Content<Vec<u8>>),
+    Link(Redirect),
+}
+
 #[get("/")]
 fn index() -> &'static str {
     "
@@ -24,61 +34,122 @@ fn index() -> &'static str {
         lists the discovered devices
     
     /device/<id>/power/<on/off>
-        powers on/off the device with given id
+        powers on/off the device with given alias or MAC address
 
     /device/<id>/mute/<on/off>
         mutes or unmutes the device with given id
+
+    /device/<id>/now-playing
+        shows the track playing on the network player of the device with given id
+
+    /device/<id>/art
+        shows the album art of the track playing on the device with given id
     "
 }
 
 #[get("/discover")]
-fn discover(shared: State<SharedData>) -> String {
+fn discover(shared: State<SharedData>) -> Result<String, Status> {
     let shared_data: &SharedData = shared.inner();
     let mut locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
-    locked_remote.discover(Duration::from_secs(5));
-    locked_remote.serialize()
+    locked_remote.discover(Duration::from_secs(5)).map_err(status)?;
+    locked_remote.serialize().map_err(status)
 }
 
 #[get("/devices")]
-fn devices(shared: State<SharedData>) -> String {
+fn devices(shared: State<SharedData>) -> Result<String, Status> {
     let shared_data: &SharedData = shared.inner();
     let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
-    locked_remote.serialize()
+    locked_remote.serialize().map_err(status)
 }
 
 #[get("/device/<id>/power/off")]
-fn power_off(shared: State<SharedData>, id: usize) {
+fn power_off(shared: State<SharedData>, id: String) -> Result<(), Status> {
     let shared_data: &SharedData = shared.inner();
     let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
-    let _res = locked_remote.device(id).commands().main().power_off();
+    let device = locked_remote.device(&id).ok_or(Status::NotFound)?;
+    device.commands().main().power_off().map_err(status)
 }
 
 #[get("/device/<id>/power/on")]
-fn power_on(shared: State<SharedData>, id: usize) {
+fn power_on(shared: State<SharedData>, id: String) -> Result<(), Status> {
     let shared_data: &SharedData = shared.inner();
     let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
-    let _res = locked_remote.device(id).commands().main().power_on();
+    let device = locked_remote.device(&id).ok_or(Status::NotFound)?;
+    device.commands().main().power_on().map_err(status)
 }
 
 #[get("/device/<id>/mute/off")]
-fn mute_off(shared: State<SharedData>, id: usize) {
+fn mute_off(shared: State<SharedData>, id: String) -> Result<(), Status> {
     let shared_data: &SharedData = shared.inner();
     let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
-    let _res = locked_remote.device(id).commands().main().mute_off();
+    let device = locked_remote.device(&id).ok_or(Status::NotFound)?;
+    device.commands().main().unmute().map_err(status)
 }
 
 #[get("/device/<id>/mute/on")]
-fn mute_on(shared: State<SharedData>, id: usize) {
+fn mute_on(shared: State<SharedData>, id: String) -> Result<(), Status> {
+    let shared_data: &SharedData = shared.inner();
+    let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
+    let device = locked_remote.device(&id).ok_or(Status::NotFound)?;
+    device.commands().main().mute().map_err(status)
+}
+
+#[get("/device/<id>/now-playing")]
+fn now_playing(shared: State<SharedData>, id: String) -> Result<String, Status> {
     let shared_data: &SharedData = shared.inner();
     let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
-    let _res = locked_remote.device(id).commands().main().mute_on();
+    let device = locked_remote.device(&id).ok_or(Status::NotFound)?;
+    let now_playing = device.commands().net().now_playing().map_err(status)?;
+    now_playing.serialize().map_err(status)
+}
+
+#[get("/device/<id>/art")]
+fn art(shared: State<SharedData>, id: String) -> Result<ArtResponse, Status> {
+    let shared_data: &SharedData = shared.inner();
+    let locked_remote = shared_data.remote.lock().expect("Cannot access remote state");
+    let device = locked_remote.device(&id).ok_or(Status::NotFound)?;
+    device.connect().map_err(status)?;
+    // Album art can take seconds to arrive, so release the remote while waiting for it
+    let device = device.clone();
+    drop(locked_remote);
+    let art = match device.album_art().map_err(status)? {
+        Some(art) => art,
+        None => device.commands().net().album_art().map_err(|e| match e {
+            IscpError::NotAvailable(_) => Status::NotFound,
+            e => status(e),
+        })?,
+    };
+    let content_type = art
+        .mime_type()
+        .and_then(ContentType::parse_flexible)
+        .unwrap_or(ContentType::Binary);
+    match art {
+        AlbumArt::Image { data, .. } => Ok(ArtResponse::Image(Content(content_type, data))),
+        AlbumArt::Link(url) => Ok(ArtResponse::Link(Redirect::to(url))),
+    }
+}
+
+fn status(e: IscpError) -> Status {
+    match e {
+        IscpError::Connect(_) | IscpError::NotConnected | IscpError::Timeout => {
+            Status::ServiceUnavailable
+        }
+        IscpError::NotAvailable(_) => Status::NotImplemented,
+        IscpError::Config(_) => Status::InternalServerError,
+        _ => Status::BadGateway,
+    }
 }
 
 fn main() {
+    let remote = iscp::Remote::load().unwrap_or_else(|e| {
+        println!("Failed to load remote state: {}", e);
+        iscp::Remote::new()
+    });
+
     rocket::ignite()
         .manage(SharedData {
-            remote: Mutex::new(iscp::Remote::load())
+            remote: Mutex::new(remote)
         })
-        .mount("/", routes![index, discover, devices, power_off, power_on, mute_off, mute_on])
+        .mount("/", routes![index, discover, devices, power_off, power_on, mute_off, mute_on, now_playing, art])
         .launch();
 }
\ No newline at end of file
@@ -0,0 +1,61 @@
+use std::process;
+use std::thread;
+use std::time::Duration;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    about = "Simulates an Onkyo receiver for testing without hardware",
+    author = "Havoc (havoc.dev)"
+)]
+struct ProgramOptions {
+    #[structopt(
+        long,
+        default_value = "0.0.0.0:60128",
+        help = "TCP address to listen on"
+    )]
+    address: String,
+    #[structopt(
+        long,
+        default_value = "0.0.0.0:60128",
+        help = "UDP address to answer discovery requests on"
+    )]
+    discovery: String,
+    #[structopt(long, help = "Disables answering discovery requests")]
+    no_discovery: bool,
+    #[structopt(long, default_value = "TX-SIM", help = "Model name")]
+    model: String,
+    #[structopt(long, default_value = "XX", help = "Destination area")]
+    area: String,
+    #[structopt(long, default_value = "0009B0000001", help = "MAC address")]
+    mac: String,
+}
+
+fn main() {
+    let options = ProgramOptions::from_args();
+    let device = iscp::Device::from(options.address, options.model, options.area, options.mac);
+    let discovery = if options.no_discovery {
+        None
+    } else {
+        Some(options.discovery.as_str())
+    };
+
+    let receiver = match iscp::MockReceiver::start(&device, discovery) {
+        Ok(receiver) => receiver,
+        Err(e) => {
+            eprintln!("Failed to start simulator: {}", e);
+            process::exit(1);
+        }
+    };
+    println!("Simulating {} on {}", device.model, receiver.address());
+    if let Some(address) = receiver.discovery_address() {
+        println!("Answering discovery on {}", address);
+    }
+
+    loop {
+        for message in receiver.take_received() {
+            println!("{}{}", message.command, message.parameter);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,221 @@
+use super::decoder::decode_buffer;
+use super::device::{ISCP_PARAM_NOT_AVAILABLE, ISCP_PARAM_QUERY};
+use super::{
+    Device, DiscoverOptions, Discovery, InputSource, IscpError, IscpMessage, PowerState, Result,
+    DEVICE_QUERY_TIMEOUT,
+};
+use bytes::BytesMut;
+use futures_util::{SinkExt, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use tokio::time::{timeout_at, Instant};
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+#[derive(Debug, Default)]
+pub struct IscpCodec;
+
+impl Decoder for IscpCodec {
+    type Item = IscpMessage;
+    type Error = IscpError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<IscpMessage>> {
+        Ok(decode_buffer(src))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<IscpMessage>> {
+        let message = decode_buffer(src);
+        if message.is_none() {
+            src.clear();
+        }
+        Ok(message)
+    }
+}
+
+impl Encoder<IscpMessage> for IscpCodec {
+    type Error = IscpError;
+
+    fn encode(&mut self, message: IscpMessage, dst: &mut BytesMut) -> Result<()> {
+        dst.extend_from_slice(message.bytes()?.as_ref());
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AsyncSession {
+    reader: FramedRead<OwnedReadHalf, IscpCodec>,
+    writer: FramedWrite<OwnedWriteHalf, IscpCodec>,
+    pending: VecDeque<IscpMessage>,
+}
+
+impl AsyncSession {
+    pub async fn connect(address: &str) -> Result<AsyncSession> {
+        let stream = TcpStream::connect(address)
+            .await
+            .map_err(IscpError::Connect)?;
+        let (reader, writer) = stream.into_split();
+        Ok(AsyncSession {
+            reader: FramedRead::new(reader, IscpCodec),
+            writer: FramedWrite::new(writer, IscpCodec),
+            pending: VecDeque::new(),
+        })
+    }
+
+    pub async fn send(&mut self, message: IscpMessage) -> Result<()> {
+        self.writer.send(message).await
+    }
+
+    pub async fn raw(&mut self, iscp_command: &str, iscp_parameter: &str) -> Result<()> {
+        self.send(IscpMessage::from(iscp_command, iscp_parameter))
+            .await
+    }
+
+    pub async fn receive(&mut self) -> Result<IscpMessage> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(message);
+        }
+        self.reader
+            .next()
+            .await
+            .unwrap_or(Err(IscpError::NotConnected))
+    }
+
+    pub async fn query(&mut self, iscp_command: &str) -> Result<IscpMessage> {
+        self.query_timeout(iscp_command, DEVICE_QUERY_TIMEOUT).await
+    }
+
+    pub async fn query_timeout(
+        &mut self,
+        iscp_command: &str,
+        timeout: Duration,
+    ) -> Result<IscpMessage> {
+        self.raw(iscp_command, ISCP_PARAM_QUERY).await?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            let message = timeout_at(deadline, self.reader.next())
+                .await
+                .map_err(|_| IscpError::Timeout)?
+                .unwrap_or(Err(IscpError::NotConnected))?;
+            // Messages arriving before the reply stay available to receive and the stream
+            if message.command != iscp_command {
+                self.pending.push_back(message);
+                continue;
+            }
+            if message.parameter == ISCP_PARAM_NOT_AVAILABLE {
+                return Err(IscpError::NotAvailable(String::from(iscp_command)));
+            }
+            return Ok(message);
+        }
+    }
+}
+
+impl Stream for AsyncSession {
+    type Item = Result<IscpMessage>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(message) = self.pending.pop_front() {
+            return Poll::Ready(Some(Ok(message)));
+        }
+        Pin::new(&mut self.reader).poll_next(cx)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AsyncDevice {
+    device: Arc<Device>,
+}
+
+impl AsyncDevice {
+    pub fn from(device: Device) -> AsyncDevice {
+        AsyncDevice {
+            device: Arc::new(device),
+        }
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    // The blocking command API runs on tokio's blocking pool over the device's persistent session
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Device) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let device = Arc::clone(&self.device);
+        tokio::task::spawn_blocking(move || f(&device))
+            .await
+            .map_err(|e| IscpError::Io(io::Error::other(e)))?
+    }
+
+    pub async fn raw(&self, iscp_command: &str, iscp_parameter: &str) -> Result<()> {
+        let message = IscpMessage::from(iscp_command, iscp_parameter);
+        self.run(move |device| device.send(message)).await
+    }
+
+    pub async fn query(&self, iscp_command: &str) -> Result<IscpMessage> {
+        let iscp_command = String::from(iscp_command);
+        self.run(move |device| device.query(&iscp_command)).await
+    }
+
+    pub async fn power_on(&self) -> Result<()> {
+        self.run(|device| device.commands().main().power_on()).await
+    }
+
+    pub async fn power_off(&self) -> Result<()> {
+        self.run(|device| device.commands().main().power_off())
+            .await
+    }
+
+    pub async fn power_state(&self) -> Result<PowerState> {
+        self.run(|device| device.commands().main().power_state())
+            .await
+    }
+
+    pub async fn mute(&self) -> Result<()> {
+        self.run(|device| device.commands().main().mute()).await
+    }
+
+    pub async fn unmute(&self) -> Result<()> {
+        self.run(|device| device.commands().main().unmute()).await
+    }
+
+    pub async fn is_muted(&self) -> Result<bool> {
+        self.run(|device| device.commands().main().is_muted()).await
+    }
+
+    pub async fn set_volume(&self, level: u8) -> Result<()> {
+        self.run(move |device| device.commands().main().set_volume(level))
+            .await
+    }
+
+    pub async fn volume(&self) -> Result<u8> {
+        self.run(|device| device.commands().main().volume()).await
+    }
+
+    pub async fn select_input(&self, source: InputSource) -> Result<()> {
+        self.run(move |device| device.commands().main().select_input(source))
+            .await
+    }
+
+    pub async fn input(&self) -> Result<InputSource> {
+        self.run(|device| device.commands().main().input()).await
+    }
+}
+
+impl Device {
+    pub async fn connect_async(&self) -> Result<AsyncSession> {
+        AsyncSession::connect(self.address.as_str()).await
+    }
+}
+
+pub async fn discover_async(options: DiscoverOptions) -> Result<Vec<Device>> {
+    tokio::task::spawn_blocking(move || Ok(Discovery::start(options)?.collect()))
+        .await
+        .map_err(|e| IscpError::Io(io::Error::other(e)))?
+}
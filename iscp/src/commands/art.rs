@@ -0,0 +1,167 @@
+use super::from_hex_bytes;
+use crate::device::ISCP_PARAM_NOT_AVAILABLE;
+use crate::IscpMessage;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageType {
+    Bmp,
+    Jpeg,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlbumArt {
+    Image {
+        image_type: ImageType,
+        data: Vec<u8>,
+    },
+    Link(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArtType {
+    Image(ImageType),
+    Link,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArtAssembler {
+    art: Option<AlbumArt>,
+    pending: Option<(ArtType, Vec<u8>)>,
+}
+
+impl ImageType {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageType::Bmp => "image/bmp",
+            ImageType::Jpeg => "image/jpeg",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageType::Bmp => "bmp",
+            ImageType::Jpeg => "jpg",
+        }
+    }
+}
+
+impl fmt::Display for ImageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ImageType::Bmp => "BMP",
+            ImageType::Jpeg => "JPEG",
+        })
+    }
+}
+
+impl AlbumArt {
+    pub fn mime_type(&self) -> Option<&'static str> {
+        match self {
+            AlbumArt::Image { image_type, .. } => Some(image_type.mime_type()),
+            AlbumArt::Link(_) => None,
+        }
+    }
+
+    fn from(art_type: ArtType, data: Vec<u8>) -> AlbumArt {
+        match art_type {
+            ArtType::Image(image_type) => AlbumArt::Image { image_type, data },
+            ArtType::Link => AlbumArt::Link(String::from_utf8_lossy(&data).trim().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for AlbumArt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlbumArt::Image { image_type, data } => {
+                write!(f, "{} ({} bytes)", image_type, data.len())
+            }
+            AlbumArt::Link(url) => f.write_str(url),
+        }
+    }
+}
+
+impl ArtType {
+    fn from_char(c: char) -> Option<ArtType> {
+        match c {
+            '0' => Some(ArtType::Image(ImageType::Bmp)),
+            '1' => Some(ArtType::Image(ImageType::Jpeg)),
+            '2' => Some(ArtType::Link),
+            _ => None,
+        }
+    }
+}
+
+impl ArtAssembler {
+    pub fn art(&self) -> Option<&AlbumArt> {
+        self.art.as_ref()
+    }
+
+    pub fn update(&mut self, message: &IscpMessage) -> bool {
+        let parameter = message.parameter.as_str();
+        if message.command != "NJA" || parameter == ISCP_PARAM_NOT_AVAILABLE {
+            return false;
+        }
+        let mut chars = parameter.chars();
+        let (type_char, flag) = match (chars.next(), chars.next()) {
+            (Some(type_char), Some(flag)) => (type_char, flag),
+            _ => return false,
+        };
+        if type_char == 'n' {
+            self.pending = None;
+            return self.art.take().is_some();
+        }
+        let art_type = match ArtType::from_char(type_char) {
+            Some(art_type) => art_type,
+            None => return false,
+        };
+        let data = match decode_chunk(art_type, chars.as_str()) {
+            Some(data) => data,
+            None => {
+                self.pending = None;
+                return false;
+            }
+        };
+        match flag {
+            '0' => {
+                self.pending = Some((art_type, data));
+                false
+            }
+            '1' => {
+                match self.pending.as_mut() {
+                    Some((pending_type, pending)) if *pending_type == art_type => {
+                        pending.extend(data)
+                    }
+                    _ => self.pending = None,
+                }
+                false
+            }
+            '2' => match self.pending.take() {
+                Some((pending_type, mut pending)) if pending_type == art_type => {
+                    pending.extend(data);
+                    self.complete(AlbumArt::from(art_type, pending))
+                }
+                _ => false,
+            },
+            '-' => {
+                self.pending = None;
+                self.complete(AlbumArt::from(art_type, data))
+            }
+            _ => false,
+        }
+    }
+
+    fn complete(&mut self, art: AlbumArt) -> bool {
+        let changed = self.art.as_ref() != Some(&art);
+        self.art = Some(art);
+        changed
+    }
+}
+
+fn decode_chunk(art_type: ArtType, chunk: &str) -> Option<Vec<u8>> {
+    match art_type {
+        ArtType::Image(_) => from_hex_bytes(chunk),
+        ArtType::Link => Some(from_hex_bytes(chunk).unwrap_or_else(|| chunk.as_bytes().to_vec())),
+    }
+}
@@ -0,0 +1,113 @@
+use super::normalize_name;
+
+pub(crate) struct Entry<T: 'static, C: 'static = ()> {
+    pub(crate) value: T,
+    pub(crate) code: C,
+    pub(crate) name: &'static str,
+    pub(crate) aliases: &'static [&'static str],
+}
+
+impl<T> Entry<T> {
+    pub(crate) const fn named(
+        value: T,
+        name: &'static str,
+        aliases: &'static [&'static str],
+    ) -> Entry<T> {
+        Entry::from(value, (), name, aliases)
+    }
+}
+
+impl<T, C> Entry<T, C> {
+    pub(crate) const fn from(
+        value: T,
+        code: C,
+        name: &'static str,
+        aliases: &'static [&'static str],
+    ) -> Entry<T, C> {
+        Entry {
+            value,
+            code,
+            name,
+            aliases,
+        }
+    }
+
+    pub(crate) fn matches(&self, normalized_name: &str) -> bool {
+        normalize_name(self.name) == normalized_name
+            || self
+                .aliases
+                .iter()
+                .any(|alias| normalize_name(alias) == normalized_name)
+    }
+}
+
+// Implements all, name, the lookup by code, the code accessor, Display and FromStr for an enum
+// listed in a table of entries, leaving out the code parts for tables without codes
+macro_rules! catalogue {
+    (
+        $type:ident,
+        $table:ident,
+        $kind:literal,
+        $from_code:ident($lookup:ty) -> $code_fn:ident($code:ty)
+    ) => {
+        catalogue!(@entries $type, $table, $kind, $code);
+
+        impl $type {
+            pub fn $from_code(code: $lookup) -> Option<$type> {
+                $table
+                    .iter()
+                    .find(|entry| entry.code == code)
+                    .map(|entry| entry.value)
+            }
+
+            pub fn $code_fn(self) -> $code {
+                self.entry().code
+            }
+        }
+    };
+    ($type:ident, $table:ident, $kind:literal) => {
+        catalogue!(@entries $type, $table, $kind, ());
+    };
+    (@entries $type:ident, $table:ident, $kind:literal, $code:ty) => {
+        impl $type {
+            pub fn all() -> impl Iterator<Item = $type> {
+                $table.iter().map(|entry| entry.value)
+            }
+
+            pub fn name(self) -> &'static str {
+                self.entry().name
+            }
+
+            fn entry(self) -> &'static $crate::commands::catalogue::Entry<$type, $code> {
+                $table
+                    .iter()
+                    .find(|entry| entry.value == self)
+                    .expect(concat!("Every ", $kind, " has a table entry"))
+            }
+        }
+
+        impl std::fmt::Display for $type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl std::str::FromStr for $type {
+            type Err = $crate::IscpError;
+
+            fn from_str(s: &str) -> std::result::Result<$type, $crate::IscpError> {
+                let name = $crate::commands::normalize_name(s);
+                $table
+                    .iter()
+                    .find(|entry| entry.matches(&name))
+                    .map(|entry| entry.value)
+                    .ok_or_else(|| {
+                        $crate::IscpError::InvalidArgument(format!(
+                            concat!("Unknown ", $kind, " {}"),
+                            s
+                        ))
+                    })
+            }
+        }
+    };
+}
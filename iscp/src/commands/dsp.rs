@@ -0,0 +1,127 @@
+use super::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DspFeature {
+    Audyssey,
+    DynamicEq,
+    DynamicVolume,
+    LateNight,
+    CinemaFilter,
+    MusicOptimizer,
+    Direct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DspLevel {
+    Off,
+    On,
+    Music,
+    Light,
+    Medium,
+    Heavy,
+    Low,
+    High,
+    Auto,
+}
+
+static FEATURES: &[Entry<DspFeature, &str>] = &[
+    Entry::from(
+        DspFeature::Audyssey,
+        "ADY",
+        "Audyssey",
+        &["MultEQ", "2EQ", "ADY"],
+    ),
+    Entry::from(DspFeature::DynamicEq, "ADQ", "Dynamic EQ", &["ADQ"]),
+    Entry::from(DspFeature::DynamicVolume, "ADV", "Dynamic Volume", &["ADV"]),
+    Entry::from(
+        DspFeature::LateNight,
+        "LTN",
+        "Late Night",
+        &["Night", "LTN"],
+    ),
+    Entry::from(
+        DspFeature::CinemaFilter,
+        "RAS",
+        "Cinema Filter",
+        &["Re-EQ", "RAS"],
+    ),
+    Entry::from(
+        DspFeature::MusicOptimizer,
+        "MOT",
+        "Music Optimizer",
+        &["Sound Retriever", "MOT"],
+    ),
+    Entry::from(DspFeature::Direct, "DIR", "Direct", &["DIR"]),
+];
+
+static LEVELS: &[Entry<DspLevel>] = &[
+    Entry::named(DspLevel::Off, "Off", &["0", "Disabled"]),
+    Entry::named(DspLevel::On, "On", &["1", "Enabled", "Movie"]),
+    Entry::named(DspLevel::Music, "Music", &[]),
+    Entry::named(DspLevel::Light, "Light", &[]),
+    Entry::named(DspLevel::Medium, "Medium", &["Mid"]),
+    Entry::named(DspLevel::Heavy, "Heavy", &[]),
+    Entry::named(DspLevel::Low, "Low", &[]),
+    Entry::named(DspLevel::High, "High", &[]),
+    Entry::named(DspLevel::Auto, "Auto", &[]),
+];
+
+catalogue!(DspFeature, FEATURES, "DSP feature", from_command(&str) -> command(&'static str));
+catalogue!(DspLevel, LEVELS, "DSP level");
+
+impl DspFeature {
+    pub fn levels(self) -> impl Iterator<Item = DspLevel> {
+        self.level_codes().iter().map(|level| level.1)
+    }
+
+    pub fn supports(self, level: DspLevel) -> bool {
+        self.levels().any(|supported| supported == level)
+    }
+
+    pub fn level_from_code(self, code: u8) -> Option<DspLevel> {
+        self.level_codes()
+            .iter()
+            .find(|level| level.0 == code)
+            .map(|level| level.1)
+    }
+
+    pub fn level_code(self, level: DspLevel) -> Option<u8> {
+        self.level_codes()
+            .iter()
+            .find(|supported| supported.1 == level)
+            .map(|supported| supported.0)
+    }
+
+    pub(crate) fn cycle_parameter(self) -> &'static str {
+        match self {
+            DspFeature::Direct => "TG",
+            _ => "UP",
+        }
+    }
+
+    fn level_codes(self) -> &'static [(u8, DspLevel)] {
+        match self {
+            DspFeature::Audyssey => &[
+                (0x00, DspLevel::Off),
+                (0x01, DspLevel::On),
+                (0x02, DspLevel::Music),
+            ],
+            DspFeature::DynamicVolume => &[
+                (0x00, DspLevel::Off),
+                (0x01, DspLevel::Light),
+                (0x02, DspLevel::Medium),
+                (0x03, DspLevel::Heavy),
+            ],
+            DspFeature::LateNight => &[
+                (0x00, DspLevel::Off),
+                (0x01, DspLevel::Low),
+                (0x02, DspLevel::High),
+                (0x03, DspLevel::Auto),
+            ],
+            DspFeature::DynamicEq
+            | DspFeature::CinemaFilter
+            | DspFeature::MusicOptimizer
+            | DspFeature::Direct => &[(0x00, DspLevel::Off), (0x01, DspLevel::On)],
+        }
+    }
+}
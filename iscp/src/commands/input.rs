@@ -0,0 +1,109 @@
+use super::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    Video1,
+    Video2,
+    Video3,
+    Video4,
+    Video5,
+    Video6,
+    Video7,
+    Extra1,
+    Extra2,
+    Extra3,
+    BdDvd,
+    StreamBox,
+    Tv,
+    Tape,
+    Tape2,
+    Phono,
+    TvCd,
+    Fm,
+    Am,
+    Tuner,
+    MusicServer,
+    InternetRadio,
+    Usb,
+    UsbRear,
+    Net,
+    UsbToggle,
+    Airplay,
+    Bluetooth,
+    UsbDac,
+    MultiChannel,
+    Xm,
+    Sirius,
+    Dab,
+    UniversalPort,
+    Line,
+    Line2,
+    Optical,
+    Coaxial,
+    Hdmi5,
+    Hdmi6,
+    Hdmi7,
+    MainSource,
+}
+
+static INPUT_SOURCES: &[Entry<InputSource, u8>] = &[
+    Entry::from(InputSource::Video1, 0x00, "VIDEO1", &["VCR/DVR", "STB/DVR"]),
+    Entry::from(
+        InputSource::Video2,
+        0x01,
+        "VIDEO2",
+        &["CBL/SAT", "CBL", "SAT"],
+    ),
+    Entry::from(InputSource::Video3, 0x02, "VIDEO3", &["GAME", "GAME/TV"]),
+    Entry::from(InputSource::Video4, 0x03, "VIDEO4", &["AUX", "AUX1"]),
+    Entry::from(InputSource::Video5, 0x04, "VIDEO5", &["AUX2", "GAME2"]),
+    Entry::from(InputSource::Video6, 0x05, "VIDEO6", &["PC"]),
+    Entry::from(InputSource::Video7, 0x06, "VIDEO7", &[]),
+    Entry::from(InputSource::Extra1, 0x07, "EXTRA1", &[]),
+    Entry::from(InputSource::Extra2, 0x08, "EXTRA2", &[]),
+    Entry::from(InputSource::Extra3, 0x09, "EXTRA3", &[]),
+    Entry::from(InputSource::BdDvd, 0x10, "BD/DVD", &["BD", "DVD"]),
+    Entry::from(InputSource::StreamBox, 0x11, "STRM BOX", &["STREAM BOX"]),
+    Entry::from(InputSource::Tv, 0x12, "TV", &[]),
+    Entry::from(InputSource::Tape, 0x20, "TAPE", &["TAPE1", "TV/TAPE"]),
+    Entry::from(InputSource::Tape2, 0x21, "TAPE2", &[]),
+    Entry::from(InputSource::Phono, 0x22, "PHONO", &[]),
+    Entry::from(InputSource::TvCd, 0x23, "TV/CD", &["CD"]),
+    Entry::from(InputSource::Fm, 0x24, "FM", &[]),
+    Entry::from(InputSource::Am, 0x25, "AM", &[]),
+    Entry::from(InputSource::Tuner, 0x26, "TUNER", &[]),
+    Entry::from(InputSource::MusicServer, 0x27, "MUSIC SERVER", &["DLNA"]),
+    Entry::from(InputSource::InternetRadio, 0x28, "INTERNET RADIO", &[]),
+    Entry::from(InputSource::Usb, 0x29, "USB", &["USB FRONT"]),
+    Entry::from(InputSource::UsbRear, 0x2A, "USB REAR", &[]),
+    Entry::from(InputSource::Net, 0x2B, "NET", &["NETWORK"]),
+    Entry::from(InputSource::UsbToggle, 0x2C, "USB TOGGLE", &[]),
+    Entry::from(InputSource::Airplay, 0x2D, "AIRPLAY", &[]),
+    Entry::from(InputSource::Bluetooth, 0x2E, "BLUETOOTH", &["BT"]),
+    Entry::from(InputSource::UsbDac, 0x2F, "USB DAC IN", &["USB DAC"]),
+    Entry::from(
+        InputSource::MultiChannel,
+        0x30,
+        "MULTI CH",
+        &["MULTI CHANNEL"],
+    ),
+    Entry::from(InputSource::Xm, 0x31, "XM", &[]),
+    Entry::from(InputSource::Sirius, 0x32, "SIRIUS", &[]),
+    Entry::from(InputSource::Dab, 0x33, "DAB", &[]),
+    Entry::from(InputSource::UniversalPort, 0x40, "UNIVERSAL PORT", &[]),
+    Entry::from(InputSource::Line, 0x41, "LINE", &["LINE1"]),
+    Entry::from(InputSource::Line2, 0x42, "LINE2", &[]),
+    Entry::from(InputSource::Optical, 0x44, "OPTICAL", &[]),
+    Entry::from(InputSource::Coaxial, 0x45, "COAXIAL", &[]),
+    Entry::from(InputSource::Hdmi5, 0x55, "HDMI5", &[]),
+    Entry::from(InputSource::Hdmi6, 0x56, "HDMI6", &[]),
+    Entry::from(InputSource::Hdmi7, 0x57, "HDMI7", &[]),
+    Entry::from(InputSource::MainSource, 0x80, "MAIN SOURCE", &["SOURCE"]),
+];
+
+catalogue!(
+    InputSource,
+    INPUT_SOURCES,
+    "input source",
+    from_code(u8) -> code(u8)
+);
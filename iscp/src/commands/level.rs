@@ -0,0 +1,39 @@
+use super::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LevelChannel {
+    Center,
+    Subwoofer,
+    Subwoofer2,
+}
+
+static CHANNELS: &[Entry<LevelChannel, &str>] = &[
+    Entry::from(LevelChannel::Center, "CTL", "Center", &["Centre", "C"]),
+    Entry::from(
+        LevelChannel::Subwoofer,
+        "SWL",
+        "Subwoofer",
+        &["Sub", "SW", "Subwoofer 1", "SW1"],
+    ),
+    Entry::from(
+        LevelChannel::Subwoofer2,
+        "SW2",
+        "Subwoofer 2",
+        &["Sub 2", "SW2"],
+    ),
+];
+
+catalogue!(LevelChannel, CHANNELS, "level channel", from_command(&str) -> command(&'static str));
+
+impl LevelChannel {
+    pub fn min_level(self) -> i8 {
+        match self {
+            LevelChannel::Center => -12,
+            LevelChannel::Subwoofer | LevelChannel::Subwoofer2 => -15,
+        }
+    }
+
+    pub fn max_level(self) -> i8 {
+        12
+    }
+}
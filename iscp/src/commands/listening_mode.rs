@@ -0,0 +1,351 @@
+use super::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListeningMode {
+    Stereo,
+    Direct,
+    Surround,
+    Film,
+    Thx,
+    Action,
+    Musical,
+    MonoMovie,
+    Orchestra,
+    Unplugged,
+    StudioMix,
+    TvLogic,
+    AllChannelStereo,
+    TheaterDimensional,
+    Enhanced,
+    Mono,
+    PureAudio,
+    Multiplex,
+    FullMono,
+    DolbyVirtual,
+    DtsSurroundSensation,
+    AudysseyDsx,
+    WholeHouse,
+    StraightDecode,
+    DolbyExDtsEs,
+    ThxCinema,
+    ThxSurroundEx,
+    ThxMusic,
+    ThxGames,
+    ThxU2Cinema,
+    ThxMusicMode,
+    ThxGamesMode,
+    PlMovie,
+    PlMusic,
+    NeoCinema,
+    NeoMusic,
+    PlThxCinema,
+    NeoThxCinema,
+    PlGame,
+    NeuralSurround,
+    NeuralThx,
+    PlThxGames,
+    NeoThxGames,
+    PlThxMusic,
+    NeoThxMusic,
+    NeuralThxCinema,
+    NeuralThxMusic,
+    NeuralThxGames,
+    PlzHeight,
+    NeoCinemaDtsSurroundSensation,
+    NeoMusicDtsSurroundSensation,
+    NeuralDigitalMusic,
+    PlzHeightThxCinema,
+    PlzHeightThxMusic,
+    PlzHeightThxGames,
+    PlzHeightThxU2Cinema,
+    PlzHeightThxU2Music,
+    PlzHeightThxU2Games,
+    NeoGame,
+    PlMovieDsx,
+    PlMusicDsx,
+    PlGameDsx,
+    NeoCinemaDsx,
+    NeoMusicDsx,
+    NeuralSurroundDsx,
+    NeuralDigitalMusicDsx,
+    DolbyExDsx,
+    AutoSurround,
+}
+
+static LISTENING_MODES: &[Entry<ListeningMode, u8>] = &[
+    Entry::from(ListeningMode::Stereo, 0x00, "STEREO", &[]),
+    Entry::from(ListeningMode::Direct, 0x01, "DIRECT", &[]),
+    Entry::from(ListeningMode::Surround, 0x02, "SURROUND", &[]),
+    Entry::from(ListeningMode::Film, 0x03, "FILM", &["GAME-RPG"]),
+    Entry::from(ListeningMode::Thx, 0x04, "THX", &[]),
+    Entry::from(ListeningMode::Action, 0x05, "ACTION", &["GAME-ACTION"]),
+    Entry::from(ListeningMode::Musical, 0x06, "MUSICAL", &["GAME-ROCK"]),
+    Entry::from(ListeningMode::MonoMovie, 0x07, "MONO MOVIE", &[]),
+    Entry::from(ListeningMode::Orchestra, 0x08, "ORCHESTRA", &["CLASSICAL"]),
+    Entry::from(ListeningMode::Unplugged, 0x09, "UNPLUGGED", &[]),
+    Entry::from(
+        ListeningMode::StudioMix,
+        0x0A,
+        "STUDIO-MIX",
+        &["ENTERTAINMENT SHOW"],
+    ),
+    Entry::from(ListeningMode::TvLogic, 0x0B, "TV LOGIC", &["DRAMA"]),
+    Entry::from(
+        ListeningMode::AllChannelStereo,
+        0x0C,
+        "ALL CH STEREO",
+        &["EXTENDED STEREO"],
+    ),
+    Entry::from(
+        ListeningMode::TheaterDimensional,
+        0x0D,
+        "THEATER-DIMENSIONAL",
+        &["FRONT STAGE SURROUND"],
+    ),
+    Entry::from(
+        ListeningMode::Enhanced,
+        0x0E,
+        "ENHANCED",
+        &["ENHANCED 7", "GAME-SPORTS"],
+    ),
+    Entry::from(ListeningMode::Mono, 0x0F, "MONO", &[]),
+    Entry::from(
+        ListeningMode::PureAudio,
+        0x11,
+        "PURE AUDIO",
+        &["PURE DIRECT"],
+    ),
+    Entry::from(ListeningMode::Multiplex, 0x12, "MULTIPLEX", &[]),
+    Entry::from(ListeningMode::FullMono, 0x13, "FULL MONO", &["MONO MUSIC"]),
+    Entry::from(
+        ListeningMode::DolbyVirtual,
+        0x14,
+        "DOLBY VIRTUAL",
+        &["SURROUND ENHANCER"],
+    ),
+    Entry::from(
+        ListeningMode::DtsSurroundSensation,
+        0x15,
+        "DTS SURROUND SENSATION",
+        &[],
+    ),
+    Entry::from(ListeningMode::AudysseyDsx, 0x16, "AUDYSSEY DSX", &[]),
+    Entry::from(ListeningMode::WholeHouse, 0x1F, "WHOLE HOUSE", &[]),
+    Entry::from(
+        ListeningMode::StraightDecode,
+        0x40,
+        "STRAIGHT DECODE",
+        &["MULTICHANNEL", "5.1CH SURROUND"],
+    ),
+    Entry::from(
+        ListeningMode::DolbyExDtsEs,
+        0x41,
+        "DOLBY EX/DTS ES",
+        &["DOLBY EX", "DTS ES"],
+    ),
+    Entry::from(ListeningMode::ThxCinema, 0x42, "THX CINEMA", &[]),
+    Entry::from(ListeningMode::ThxSurroundEx, 0x43, "THX SURROUND EX", &[]),
+    Entry::from(ListeningMode::ThxMusic, 0x44, "THX MUSIC", &[]),
+    Entry::from(ListeningMode::ThxGames, 0x45, "THX GAMES", &[]),
+    Entry::from(
+        ListeningMode::ThxU2Cinema,
+        0x50,
+        "THX U2/S2/I/S CINEMA",
+        &["THX U2 CINEMA"],
+    ),
+    Entry::from(ListeningMode::ThxMusicMode, 0x51, "THX MUSIC MODE", &[]),
+    Entry::from(ListeningMode::ThxGamesMode, 0x52, "THX GAMES MODE", &[]),
+    Entry::from(
+        ListeningMode::PlMovie,
+        0x80,
+        "PLII MOVIE",
+        &["PLIIX MOVIE", "DOLBY SURROUND"],
+    ),
+    Entry::from(ListeningMode::PlMusic, 0x81, "PLII MUSIC", &["PLIIX MUSIC"]),
+    Entry::from(
+        ListeningMode::NeoCinema,
+        0x82,
+        "NEO:6 CINEMA",
+        &["NEO:X CINEMA"],
+    ),
+    Entry::from(
+        ListeningMode::NeoMusic,
+        0x83,
+        "NEO:6 MUSIC",
+        &["NEO:X MUSIC"],
+    ),
+    Entry::from(
+        ListeningMode::PlThxCinema,
+        0x84,
+        "PLII THX CINEMA",
+        &["PLIIX THX CINEMA"],
+    ),
+    Entry::from(
+        ListeningMode::NeoThxCinema,
+        0x85,
+        "NEO:6 THX CINEMA",
+        &["NEO:X THX CINEMA"],
+    ),
+    Entry::from(ListeningMode::PlGame, 0x86, "PLII GAME", &["PLIIX GAME"]),
+    Entry::from(
+        ListeningMode::NeuralSurround,
+        0x87,
+        "NEURAL SURROUND",
+        &["NEURAL SURR"],
+    ),
+    Entry::from(
+        ListeningMode::NeuralThx,
+        0x88,
+        "NEURAL THX",
+        &["NEURAL SURROUND THX"],
+    ),
+    Entry::from(
+        ListeningMode::PlThxGames,
+        0x89,
+        "PLII THX GAMES",
+        &["PLIIX THX GAMES"],
+    ),
+    Entry::from(
+        ListeningMode::NeoThxGames,
+        0x8A,
+        "NEO:6 THX GAMES",
+        &["NEO:X THX GAMES"],
+    ),
+    Entry::from(
+        ListeningMode::PlThxMusic,
+        0x8B,
+        "PLII THX MUSIC",
+        &["PLIIX THX MUSIC"],
+    ),
+    Entry::from(
+        ListeningMode::NeoThxMusic,
+        0x8C,
+        "NEO:6 THX MUSIC",
+        &["NEO:X THX MUSIC"],
+    ),
+    Entry::from(
+        ListeningMode::NeuralThxCinema,
+        0x8D,
+        "NEURAL THX CINEMA",
+        &[],
+    ),
+    Entry::from(ListeningMode::NeuralThxMusic, 0x8E, "NEURAL THX MUSIC", &[]),
+    Entry::from(ListeningMode::NeuralThxGames, 0x8F, "NEURAL THX GAMES", &[]),
+    Entry::from(ListeningMode::PlzHeight, 0x90, "PLIIZ HEIGHT", &[]),
+    Entry::from(
+        ListeningMode::NeoCinemaDtsSurroundSensation,
+        0x91,
+        "NEO:6 CINEMA DTS SURROUND SENSATION",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::NeoMusicDtsSurroundSensation,
+        0x92,
+        "NEO:6 MUSIC DTS SURROUND SENSATION",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::NeuralDigitalMusic,
+        0x93,
+        "NEURAL DIGITAL MUSIC",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::PlzHeightThxCinema,
+        0x94,
+        "PLIIZ HEIGHT THX CINEMA",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::PlzHeightThxMusic,
+        0x95,
+        "PLIIZ HEIGHT THX MUSIC",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::PlzHeightThxGames,
+        0x96,
+        "PLIIZ HEIGHT THX GAMES",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::PlzHeightThxU2Cinema,
+        0x97,
+        "PLIIZ HEIGHT THX U2/S2 CINEMA",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::PlzHeightThxU2Music,
+        0x98,
+        "PLIIZ HEIGHT THX U2/S2 MUSIC",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::PlzHeightThxU2Games,
+        0x99,
+        "PLIIZ HEIGHT THX U2/S2 GAMES",
+        &[],
+    ),
+    Entry::from(ListeningMode::NeoGame, 0x9A, "NEO:X GAME", &[]),
+    Entry::from(
+        ListeningMode::PlMovieDsx,
+        0xA0,
+        "PLII MOVIE AUDYSSEY DSX",
+        &["PLIIX MOVIE AUDYSSEY DSX"],
+    ),
+    Entry::from(
+        ListeningMode::PlMusicDsx,
+        0xA1,
+        "PLII MUSIC AUDYSSEY DSX",
+        &["PLIIX MUSIC AUDYSSEY DSX"],
+    ),
+    Entry::from(
+        ListeningMode::PlGameDsx,
+        0xA2,
+        "PLII GAME AUDYSSEY DSX",
+        &["PLIIX GAME AUDYSSEY DSX"],
+    ),
+    Entry::from(
+        ListeningMode::NeoCinemaDsx,
+        0xA3,
+        "NEO:6 CINEMA AUDYSSEY DSX",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::NeoMusicDsx,
+        0xA4,
+        "NEO:6 MUSIC AUDYSSEY DSX",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::NeuralSurroundDsx,
+        0xA5,
+        "NEURAL SURROUND AUDYSSEY DSX",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::NeuralDigitalMusicDsx,
+        0xA6,
+        "NEURAL DIGITAL MUSIC AUDYSSEY DSX",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::DolbyExDsx,
+        0xA7,
+        "DOLBY EX AUDYSSEY DSX",
+        &[],
+    ),
+    Entry::from(
+        ListeningMode::AutoSurround,
+        0xFF,
+        "AUTO SURROUND",
+        &["AUTO"],
+    ),
+];
+
+catalogue!(
+    ListeningMode,
+    LISTENING_MODES,
+    "listening mode",
+    from_code(u8) -> code(u8)
+);
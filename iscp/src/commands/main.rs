@@ -1,82 +1,254 @@
-use super::{to_signed_hex_str, TONE_MAX_LEVEL, TONE_MIN_LEVEL, VOLUME_MAX_LEVEL};
-use crate::Device;
-use std::io::Result;
+use super::{
+    check_tone_level, from_hex_str, from_signed_hex_str, parse_tone, to_signed_hex_str, DspFeature,
+    DspLevel, InputSource, LevelChannel, ListeningMode, PowerState, Speaker, Tone, ToneSetting,
+    Zone, ZoneCommands,
+};
+use crate::{Device, IscpError, Result};
 
 pub struct MainCommands<'b> {
     device: &'b Device,
 }
 
 impl<'b> MainCommands<'b> {
-    pub fn from(device: &Device) -> MainCommands {
+    pub fn from(device: &Device) -> MainCommands<'_> {
         MainCommands { device }
     }
 
     pub fn power_off(&self) -> Result<()> {
-        self.device.raw("PWR", "00")
+        ZoneCommands::power_off(self)
     }
 
     pub fn power_on(&self) -> Result<()> {
-        self.device.raw("PWR", "01")
+        ZoneCommands::power_on(self)
+    }
+
+    pub fn power_state(&self) -> Result<PowerState> {
+        ZoneCommands::power_state(self)
     }
 
     pub fn unmute(&self) -> Result<()> {
-        self.device.raw("AMT", "00")
+        ZoneCommands::unmute(self)
     }
 
     pub fn mute(&self) -> Result<()> {
-        self.device.raw("AMT", "01")
+        ZoneCommands::mute(self)
     }
 
-    pub fn set_volume(&self, mut level: u8) -> Result<()> {
-        if level > VOLUME_MAX_LEVEL {
-            level = VOLUME_MAX_LEVEL;
-        }
-        self.device.raw("MVL", format!("{:02X}", level).as_str())
+    pub fn is_muted(&self) -> Result<bool> {
+        ZoneCommands::is_muted(self)
+    }
+
+    pub fn set_volume(&self, level: u8) -> Result<()> {
+        ZoneCommands::set_volume(self, level)
     }
 
     pub fn set_volume_up(&self) -> Result<()> {
-        self.device.raw("MVL", "UP")
+        ZoneCommands::set_volume_up(self)
     }
 
     pub fn set_volume_down(&self) -> Result<()> {
-        self.device.raw("MVL", "DOWN")
+        ZoneCommands::set_volume_down(self)
     }
 
-    pub fn set_tone_front_bass(&self, level: i8) -> Result<()> {
+    pub fn volume(&self) -> Result<u8> {
+        ZoneCommands::volume(self)
+    }
+
+    pub fn select_input(&self, source: InputSource) -> Result<()> {
+        ZoneCommands::select_input(self, source)
+    }
+
+    pub fn input_up(&self) -> Result<()> {
+        ZoneCommands::input_up(self)
+    }
+
+    pub fn input_down(&self) -> Result<()> {
+        ZoneCommands::input_down(self)
+    }
+
+    pub fn input(&self) -> Result<InputSource> {
+        ZoneCommands::input(self)
+    }
+
+    pub fn set_listening_mode(&self, mode: ListeningMode) -> Result<()> {
+        self.device
+            .raw("LMD", format!("{:02X}", mode.code()).as_str())
+    }
+
+    pub fn listening_mode_up(&self) -> Result<()> {
+        self.device.raw("LMD", "UP")
+    }
+
+    pub fn listening_mode_down(&self) -> Result<()> {
+        self.device.raw("LMD", "DOWN")
+    }
+
+    pub fn listening_mode_movie(&self) -> Result<()> {
+        self.device.raw("LMD", "MOVIE")
+    }
+
+    pub fn listening_mode_music(&self) -> Result<()> {
+        self.device.raw("LMD", "MUSIC")
+    }
+
+    pub fn listening_mode_game(&self) -> Result<()> {
+        self.device.raw("LMD", "GAME")
+    }
+
+    pub fn listening_mode(&self) -> Result<ListeningMode> {
+        let reply = self.device.query("LMD")?;
+        from_hex_str(&reply.parameter)
+            .and_then(ListeningMode::from_code)
+            .ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    pub fn set_tone(&self, speaker: Speaker, setting: ToneSetting, level: i8) -> Result<()> {
+        self.check_tone(speaker, setting)?;
+        check_tone_level(level)?;
         self.device.raw(
-            "TFR",
-            format!(
-                "B{}",
-                to_signed_hex_str(level, TONE_MAX_LEVEL, TONE_MIN_LEVEL)
-            )
-            .as_str(),
+            speaker.command(),
+            format!("{}{}", setting.prefix(), to_signed_hex_str(level)).as_str(),
         )
     }
 
-    pub fn set_tone_front_treble(&self, level: i8) -> Result<()> {
+    pub fn set_tone_up(&self, speaker: Speaker, setting: ToneSetting) -> Result<()> {
+        self.check_tone(speaker, setting)?;
+        self.device.raw(
+            speaker.command(),
+            format!("{}UP", setting.prefix()).as_str(),
+        )
+    }
+
+    pub fn set_tone_down(&self, speaker: Speaker, setting: ToneSetting) -> Result<()> {
+        self.check_tone(speaker, setting)?;
         self.device.raw(
-            "TFR",
-            format!(
-                "T{}",
-                to_signed_hex_str(level, TONE_MAX_LEVEL, TONE_MIN_LEVEL)
-            )
-            .as_str(),
+            speaker.command(),
+            format!("{}DOWN", setting.prefix()).as_str(),
         )
     }
 
+    pub fn tone(&self, speaker: Speaker) -> Result<Tone> {
+        self.check_tone(speaker, ToneSetting::Bass)?;
+        let reply = self.device.query(speaker.command())?;
+        parse_tone(&reply.parameter).ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    pub fn set_tone_front_bass(&self, level: i8) -> Result<()> {
+        self.set_tone(Speaker::Front, ToneSetting::Bass, level)
+    }
+
+    pub fn set_tone_front_treble(&self, level: i8) -> Result<()> {
+        self.set_tone(Speaker::Front, ToneSetting::Treble, level)
+    }
+
+    pub fn tone_front(&self) -> Result<Tone> {
+        self.tone(Speaker::Front)
+    }
+
     pub fn set_tone_front_bass_up(&self) -> Result<()> {
-        self.device.raw("TFR", "BUP")
+        self.set_tone_up(Speaker::Front, ToneSetting::Bass)
     }
 
     pub fn set_tone_front_bass_down(&self) -> Result<()> {
-        self.device.raw("TFR", "BDOWN")
+        self.set_tone_down(Speaker::Front, ToneSetting::Bass)
     }
 
     pub fn set_tone_front_treble_up(&self) -> Result<()> {
-        self.device.raw("TFR", "TUP")
+        self.set_tone_up(Speaker::Front, ToneSetting::Treble)
     }
 
     pub fn set_tone_front_treble_down(&self) -> Result<()> {
-        self.device.raw("TFR", "TDOWN")
+        self.set_tone_down(Speaker::Front, ToneSetting::Treble)
+    }
+
+    pub fn set_channel_level(&self, channel: LevelChannel, level: i8) -> Result<()> {
+        check_level(channel, level)?;
+        self.device
+            .raw(channel.command(), to_signed_hex_str(level).as_str())
+    }
+
+    pub fn set_channel_level_up(&self, channel: LevelChannel) -> Result<()> {
+        self.device.raw(channel.command(), "UP")
+    }
+
+    pub fn set_channel_level_down(&self, channel: LevelChannel) -> Result<()> {
+        self.device.raw(channel.command(), "DOWN")
+    }
+
+    pub fn channel_level(&self, channel: LevelChannel) -> Result<i8> {
+        let reply = self.device.query(channel.command())?;
+        from_signed_hex_str(&reply.parameter).ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    pub fn set_center_level(&self, level: i8) -> Result<()> {
+        self.set_channel_level(LevelChannel::Center, level)
+    }
+
+    pub fn center_level(&self) -> Result<i8> {
+        self.channel_level(LevelChannel::Center)
+    }
+
+    pub fn set_subwoofer_level(&self, level: i8) -> Result<()> {
+        self.set_channel_level(LevelChannel::Subwoofer, level)
+    }
+
+    pub fn subwoofer_level(&self) -> Result<i8> {
+        self.channel_level(LevelChannel::Subwoofer)
+    }
+
+    pub fn set_dsp(&self, feature: DspFeature, level: DspLevel) -> Result<()> {
+        let code = feature
+            .level_code(level)
+            .ok_or_else(|| IscpError::Unsupported(format!("{} has no {} level", feature, level)))?;
+        self.device
+            .raw(feature.command(), format!("{:02X}", code).as_str())
+    }
+
+    pub fn cycle_dsp(&self, feature: DspFeature) -> Result<()> {
+        self.device
+            .raw(feature.command(), feature.cycle_parameter())
+    }
+
+    pub fn dsp(&self, feature: DspFeature) -> Result<DspLevel> {
+        let reply = self.device.query(feature.command())?;
+        from_hex_str(&reply.parameter)
+            .and_then(|code| feature.level_from_code(code))
+            .ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    fn check_tone(&self, speaker: Speaker, setting: ToneSetting) -> Result<()> {
+        if !speaker.supports(setting) {
+            return Err(IscpError::Unsupported(format!(
+                "{} has no {} control",
+                speaker, setting
+            )));
+        }
+        match self.device.info.as_ref() {
+            Some(info) => info.check_zone(speaker.zone()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'b> ZoneCommands for MainCommands<'b> {
+    fn device(&self) -> &Device {
+        self.device
+    }
+
+    fn zone(&self) -> Zone {
+        Zone::Main
+    }
+}
+
+fn check_level(channel: LevelChannel, level: i8) -> Result<()> {
+    if level < channel.min_level() || level > channel.max_level() {
+        return Err(IscpError::InvalidArgument(format!(
+            "{} level {} is out of range ({} to {})",
+            channel,
+            level,
+            channel.min_level(),
+            channel.max_level()
+        )));
     }
+    Ok(())
 }
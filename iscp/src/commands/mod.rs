@@ -1,42 +1,173 @@
+mod art;
+#[macro_use]
+mod catalogue;
+mod dsp;
+mod input;
+mod level;
+mod listening_mode;
 mod main;
+mod net;
+mod now_playing;
+mod rds;
+mod tone;
+mod tuner;
+mod zone;
 
-use super::Device;
+use super::{Device, IscpError, Result};
+use catalogue::Entry;
 
+pub use art::{AlbumArt, ArtAssembler, ImageType};
+pub use dsp::{DspFeature, DspLevel};
+pub use input::InputSource;
+pub use level::LevelChannel;
+pub use listening_mode::ListeningMode;
 pub use main::MainCommands;
+pub use net::{NetCommands, NetKey};
+pub use now_playing::{NowPlaying, PlayStatus, RepeatMode, ShuffleMode};
+pub use rds::{ProgramType, RdsDisplay, RdsInfo};
+pub use tone::{Speaker, ToneSetting};
+pub use tuner::{Band, Frequency, TunerCommands};
+pub use zone::{Zone, Zone2Commands, Zone3Commands, Zone4Commands, ZoneCommands};
 
 pub const VOLUME_MAX_LEVEL: u8 = 100;
 pub const TONE_MAX_LEVEL: i8 = 10;
 pub const TONE_MIN_LEVEL: i8 = -10;
+pub const PRESET_MAX_NUMBER: u8 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    On,
+    Standby,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tone {
+    pub bass: i8,
+    pub treble: i8,
+}
 
 pub struct Commands<'a> {
     main: MainCommands<'a>,
+    zone2: Zone2Commands<'a>,
+    zone3: Zone3Commands<'a>,
+    zone4: Zone4Commands<'a>,
+    tuner: TunerCommands<'a>,
+    net: NetCommands<'a>,
 }
 
 impl<'a> Commands<'a> {
-    pub fn from(device: &Device) -> Commands {
+    pub fn from(device: &Device) -> Commands<'_> {
         Commands {
             main: MainCommands::from(device),
+            zone2: Zone2Commands::from(device),
+            zone3: Zone3Commands::from(device),
+            zone4: Zone4Commands::from(device),
+            tuner: TunerCommands::from(device),
+            net: NetCommands::from(device),
         }
     }
 
-    pub fn main(&self) -> &MainCommands {
+    pub fn main(&self) -> &MainCommands<'a> {
         &self.main
     }
+
+    pub fn zone2(&self) -> &Zone2Commands<'a> {
+        &self.zone2
+    }
+
+    pub fn zone3(&self) -> &Zone3Commands<'a> {
+        &self.zone3
+    }
+
+    pub fn zone4(&self) -> &Zone4Commands<'a> {
+        &self.zone4
+    }
+
+    pub fn tuner(&self) -> &TunerCommands<'a> {
+        &self.tuner
+    }
+
+    pub fn net(&self) -> &NetCommands<'a> {
+        &self.net
+    }
+
+    pub fn zone(&self, zone: Zone) -> &dyn ZoneCommands {
+        match zone {
+            Zone::Main => &self.main,
+            Zone::Zone2 => &self.zone2,
+            Zone::Zone3 => &self.zone3,
+            Zone::Zone4 => &self.zone4,
+        }
+    }
+}
+
+pub(crate) fn check_tone_level(level: i8) -> Result<()> {
+    if !(TONE_MIN_LEVEL..=TONE_MAX_LEVEL).contains(&level) {
+        return Err(IscpError::InvalidArgument(format!(
+            "Tone level {} is out of range ({} to {})",
+            level, TONE_MIN_LEVEL, TONE_MAX_LEVEL
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn to_signed_hex_str(v: i8) -> String {
+    match v {
+        0 => String::from("00"),
+        v if v < 0 => format!("-{:X}", v.unsigned_abs()),
+        v => format!("+{:X}", v),
+    }
+}
+
+pub(crate) fn from_signed_hex_str(s: &str) -> Option<i8> {
+    let (negative, digits) = match s.get(..1)? {
+        "+" => (false, s.get(1..)?),
+        "-" => (true, s.get(1..)?),
+        _ => (false, s),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = i8::from_str_radix(digits, 16).ok()?;
+    Some(if negative { -value } else { value })
 }
 
-fn to_signed_hex_str(v: i8, max: i8, min: i8) -> String {
-    let mut value = if v > max {
-        max
-    } else if v < min {
-        min
-    } else {
-        v
+pub(crate) fn from_hex_str(s: &str) -> Option<u8> {
+    u8::from_str_radix(s, 16).ok()
+}
+
+pub(crate) fn from_hex_bytes(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| s.get(i..i + 2).and_then(from_hex_str))
+        .collect()
+}
+
+pub(crate) fn parse_tone(s: &str) -> Option<Tone> {
+    let (bass, treble) = match s.find('T') {
+        Some(treble_pos) => (s.get(..treble_pos)?, s.get(treble_pos + 1..)),
+        None => (s, None),
     };
-    let sign = if value < 0 {
-        value *= -1;
-        "-"
-    } else {
-        "+"
+    if bass.is_empty() && treble.is_none() {
+        return None;
+    }
+    let bass = match bass {
+        "" => 0,
+        bass => from_signed_hex_str(bass.strip_prefix('B')?)?,
+    };
+    let treble = match treble {
+        Some(treble) => from_signed_hex_str(treble)?,
+        None => 0,
     };
-    format!("{}{:X}", sign, value)
+    Some(Tone { bass, treble })
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
 }
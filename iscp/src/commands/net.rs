@@ -0,0 +1,195 @@
+use super::now_playing::NOW_PLAYING_COMMANDS;
+use super::{AlbumArt, ArtAssembler, Entry, NowPlaying};
+use crate::device::ISCP_PARAM_NOT_AVAILABLE;
+use crate::{Device, IscpError, Result};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+pub const NET_ART_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NetKey {
+    Play,
+    Stop,
+    Pause,
+    PlayPause,
+    TrackUp,
+    TrackDown,
+    FastForward,
+    Rewind,
+    Repeat,
+    Random,
+    RepeatShuffle,
+    Display,
+    Album,
+    Artist,
+    Genre,
+    Playlist,
+    Right,
+    Left,
+    Up,
+    Down,
+    Select,
+    Number0,
+    Number1,
+    Number2,
+    Number3,
+    Number4,
+    Number5,
+    Number6,
+    Number7,
+    Number8,
+    Number9,
+    Delete,
+    Caps,
+    Setup,
+    Return,
+    ChannelUp,
+    ChannelDown,
+    Menu,
+    TopMenu,
+    Mode,
+    List,
+    Memory,
+    F1,
+    F2,
+    Sort,
+}
+
+static NET_KEYS: &[Entry<NetKey, &str>] = &[
+    Entry::from(NetKey::Play, "PLAY", "Play", &[]),
+    Entry::from(NetKey::Stop, "STOP", "Stop", &[]),
+    Entry::from(NetKey::Pause, "PAUSE", "Pause", &[]),
+    Entry::from(NetKey::PlayPause, "P/P", "Play/Pause", &["Toggle"]),
+    Entry::from(NetKey::TrackUp, "TRUP", "Track Up", &["Next"]),
+    Entry::from(
+        NetKey::TrackDown,
+        "TRDN",
+        "Track Down",
+        &["Prev", "Previous"],
+    ),
+    Entry::from(NetKey::FastForward, "FF", "Fast Forward", &["FF"]),
+    Entry::from(NetKey::Rewind, "REW", "Rewind", &["REW"]),
+    Entry::from(NetKey::Repeat, "REPEAT", "Repeat", &[]),
+    Entry::from(NetKey::Random, "RANDOM", "Random", &["Shuffle"]),
+    Entry::from(NetKey::RepeatShuffle, "REP/SHF", "Repeat/Shuffle", &[]),
+    Entry::from(NetKey::Display, "DISPLAY", "Display", &[]),
+    Entry::from(NetKey::Album, "ALBUM", "Album", &[]),
+    Entry::from(NetKey::Artist, "ARTIST", "Artist", &[]),
+    Entry::from(NetKey::Genre, "GENRE", "Genre", &[]),
+    Entry::from(NetKey::Playlist, "PLAYLIST", "Playlist", &[]),
+    Entry::from(NetKey::Right, "RIGHT", "Right", &[]),
+    Entry::from(NetKey::Left, "LEFT", "Left", &[]),
+    Entry::from(NetKey::Up, "UP", "Up", &[]),
+    Entry::from(NetKey::Down, "DOWN", "Down", &[]),
+    Entry::from(NetKey::Select, "SELECT", "Select", &["Enter", "OK"]),
+    Entry::from(NetKey::Number0, "0", "0", &[]),
+    Entry::from(NetKey::Number1, "1", "1", &[]),
+    Entry::from(NetKey::Number2, "2", "2", &[]),
+    Entry::from(NetKey::Number3, "3", "3", &[]),
+    Entry::from(NetKey::Number4, "4", "4", &[]),
+    Entry::from(NetKey::Number5, "5", "5", &[]),
+    Entry::from(NetKey::Number6, "6", "6", &[]),
+    Entry::from(NetKey::Number7, "7", "7", &[]),
+    Entry::from(NetKey::Number8, "8", "8", &[]),
+    Entry::from(NetKey::Number9, "9", "9", &[]),
+    Entry::from(NetKey::Delete, "DELETE", "Delete", &[]),
+    Entry::from(NetKey::Caps, "CAPS", "Caps", &[]),
+    Entry::from(NetKey::Setup, "SETUP", "Setup", &[]),
+    Entry::from(NetKey::Return, "RETURN", "Return", &["Back"]),
+    Entry::from(NetKey::ChannelUp, "CHUP", "Channel Up", &["CHUP"]),
+    Entry::from(NetKey::ChannelDown, "CHDN", "Channel Down", &["CHDN"]),
+    Entry::from(NetKey::Menu, "MENU", "Menu", &[]),
+    Entry::from(NetKey::TopMenu, "TOP", "Top Menu", &["Top"]),
+    Entry::from(NetKey::Mode, "MODE", "Mode", &[]),
+    Entry::from(NetKey::List, "LIST", "List", &[]),
+    Entry::from(NetKey::Memory, "MEMORY", "Memory", &["Favorite"]),
+    Entry::from(NetKey::F1, "F1", "F1", &[]),
+    Entry::from(NetKey::F2, "F2", "F2", &[]),
+    Entry::from(NetKey::Sort, "SORT", "Sort", &[]),
+];
+
+catalogue!(NetKey, NET_KEYS, "net key", from_code(&str) -> code(&'static str));
+
+impl NetKey {
+    pub fn number(digit: u8) -> Option<NetKey> {
+        NetKey::from_code(&digit.to_string())
+    }
+}
+
+pub struct NetCommands<'b> {
+    device: &'b Device,
+}
+
+impl<'b> NetCommands<'b> {
+    pub fn from(device: &Device) -> NetCommands<'_> {
+        NetCommands { device }
+    }
+
+    pub fn press(&self, key: NetKey) -> Result<()> {
+        self.device.raw("NTC", key.code())
+    }
+
+    pub fn play(&self) -> Result<()> {
+        self.press(NetKey::Play)
+    }
+
+    pub fn pause(&self) -> Result<()> {
+        self.press(NetKey::Pause)
+    }
+
+    pub fn play_pause(&self) -> Result<()> {
+        self.press(NetKey::PlayPause)
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        self.press(NetKey::Stop)
+    }
+
+    pub fn next_track(&self) -> Result<()> {
+        self.press(NetKey::TrackUp)
+    }
+
+    pub fn previous_track(&self) -> Result<()> {
+        self.press(NetKey::TrackDown)
+    }
+
+    pub fn now_playing(&self) -> Result<NowPlaying> {
+        let mut now_playing = NowPlaying::default();
+        for command in NOW_PLAYING_COMMANDS {
+            match self.device.query(command) {
+                Ok(reply) => {
+                    now_playing.update(&reply);
+                }
+                Err(IscpError::NotAvailable(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(now_playing)
+    }
+
+    pub fn album_art(&self) -> Result<AlbumArt> {
+        let messages = self.device.subscribe()?;
+        self.device.raw("NJA", "REQ")?;
+        let mut assembler = ArtAssembler::default();
+        let deadline = Instant::now() + NET_ART_TIMEOUT;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let message = messages.recv_timeout(remaining).map_err(|e| match e {
+                RecvTimeoutError::Timeout => IscpError::Timeout,
+                RecvTimeoutError::Disconnected => IscpError::NotConnected,
+            })?;
+            if message.command != "NJA" {
+                continue;
+            }
+            if message.parameter == ISCP_PARAM_NOT_AVAILABLE || message.parameter.starts_with('n') {
+                return Err(IscpError::NotAvailable(String::from("NJA")));
+            }
+            if assembler.update(&message) {
+                if let Some(art) = assembler.art() {
+                    return Ok(art.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::device::ISCP_PARAM_NOT_AVAILABLE;
+use crate::{IscpMessage, Result};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+pub(crate) static NOW_PLAYING_COMMANDS: &[&str] = &["NTI", "NAT", "NAL", "NTM", "NTR", "NST"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum PlayStatus {
+    Stopped,
+    Playing,
+    Paused,
+    FastForward,
+    FastRewind,
+    EndOfFile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum RepeatMode {
+    Off,
+    All,
+    Folder,
+    One,
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ShuffleMode {
+    Off,
+    All,
+    Album,
+    Folder,
+    Disabled,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NowPlaying {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    #[serde(serialize_with = "serialize_secs")]
+    pub elapsed: Option<Duration>,
+    #[serde(serialize_with = "serialize_secs")]
+    pub total: Option<Duration>,
+    pub track: Option<u16>,
+    pub track_count: Option<u16>,
+    pub status: Option<PlayStatus>,
+    pub repeat: Option<RepeatMode>,
+    pub shuffle: Option<ShuffleMode>,
+}
+
+impl PlayStatus {
+    pub fn from_char(c: char) -> Option<PlayStatus> {
+        match c {
+            'S' => Some(PlayStatus::Stopped),
+            'P' => Some(PlayStatus::Playing),
+            'p' => Some(PlayStatus::Paused),
+            'F' => Some(PlayStatus::FastForward),
+            'R' => Some(PlayStatus::FastRewind),
+            'E' => Some(PlayStatus::EndOfFile),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PlayStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PlayStatus::Stopped => "Stopped",
+            PlayStatus::Playing => "Playing",
+            PlayStatus::Paused => "Paused",
+            PlayStatus::FastForward => "Fast Forward",
+            PlayStatus::FastRewind => "Fast Rewind",
+            PlayStatus::EndOfFile => "End of File",
+        })
+    }
+}
+
+impl RepeatMode {
+    pub fn from_char(c: char) -> Option<RepeatMode> {
+        match c {
+            '-' => Some(RepeatMode::Off),
+            'R' => Some(RepeatMode::All),
+            'F' => Some(RepeatMode::Folder),
+            '1' => Some(RepeatMode::One),
+            'x' => Some(RepeatMode::Disabled),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RepeatMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RepeatMode::Off => "Off",
+            RepeatMode::All => "All",
+            RepeatMode::Folder => "Folder",
+            RepeatMode::One => "One",
+            RepeatMode::Disabled => "Disabled",
+        })
+    }
+}
+
+impl ShuffleMode {
+    pub fn from_char(c: char) -> Option<ShuffleMode> {
+        match c {
+            '-' => Some(ShuffleMode::Off),
+            'S' => Some(ShuffleMode::All),
+            'A' => Some(ShuffleMode::Album),
+            'F' => Some(ShuffleMode::Folder),
+            'x' => Some(ShuffleMode::Disabled),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ShuffleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ShuffleMode::Off => "Off",
+            ShuffleMode::All => "All",
+            ShuffleMode::Album => "Album",
+            ShuffleMode::Folder => "Folder",
+            ShuffleMode::Disabled => "Disabled",
+        })
+    }
+}
+
+impl NowPlaying {
+    pub fn update(&mut self, message: &IscpMessage) -> bool {
+        let parameter = message.parameter.as_str();
+        if parameter == ISCP_PARAM_NOT_AVAILABLE {
+            return false;
+        }
+        let previous = self.clone();
+        match message.command.as_str() {
+            "NTI" => self.title = text(parameter),
+            "NAT" => self.artist = text(parameter),
+            "NAL" => self.album = text(parameter),
+            "NTM" => {
+                let (elapsed, total) = parameter.split_once('/').unwrap_or((parameter, ""));
+                self.elapsed = parse_time(elapsed);
+                self.total = parse_time(total);
+            }
+            "NTR" => {
+                let (track, track_count) = parameter.split_once('/').unwrap_or((parameter, ""));
+                self.track = track.parse().ok();
+                self.track_count = track_count.parse().ok();
+            }
+            "NST" => {
+                let mut chars = parameter.chars();
+                self.status = chars.next().and_then(PlayStatus::from_char);
+                self.repeat = chars.next().and_then(RepeatMode::from_char);
+                self.shuffle = chars.next().and_then(ShuffleMode::from_char);
+            }
+            _ => {}
+        }
+        *self != previous
+    }
+
+    pub fn serialize(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn text(parameter: &str) -> Option<String> {
+    Some(String::from(parameter.trim())).filter(|text| !text.is_empty())
+}
+
+fn parse_time(s: &str) -> Option<Duration> {
+    s.split(':')
+        .try_fold(0, |secs, part| {
+            part.parse::<u64>().ok().map(|value| secs * 60 + value)
+        })
+        .map(Duration::from_secs)
+}
+
+fn serialize_secs<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_some(&duration.as_secs()),
+        None => serializer.serialize_none(),
+    }
+}
@@ -0,0 +1,205 @@
+use super::{from_hex_bytes, from_hex_str, Band, Entry, Frequency, InputSource};
+use crate::device::ISCP_PARAM_NOT_AVAILABLE;
+use crate::IscpMessage;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RdsDisplay {
+    RadioText,
+    ProgramType,
+    TrafficProgram,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProgramType {
+    News,
+    CurrentAffairs,
+    Information,
+    Sport,
+    Education,
+    Drama,
+    Culture,
+    Science,
+    Varied,
+    PopMusic,
+    RockMusic,
+    EasyListening,
+    LightClassical,
+    SeriousClassical,
+    OtherMusic,
+    Weather,
+    Finance,
+    Children,
+    SocialAffairs,
+    Religion,
+    PhoneIn,
+    Travel,
+    Leisure,
+    JazzMusic,
+    CountryMusic,
+    NationalMusic,
+    OldiesMusic,
+    FolkMusic,
+    Documentary,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RdsInfo {
+    pub input: Option<InputSource>,
+    pub frequency: Option<Frequency>,
+    pub display: Option<RdsDisplay>,
+    pub active: bool,
+    pub station: Option<String>,
+    pub radio_text: Option<String>,
+    pub program_type: Option<ProgramType>,
+}
+
+static DISPLAYS: &[Entry<RdsDisplay, u8>] = &[
+    Entry::from(RdsDisplay::RadioText, 0x00, "Radio Text", &["RT", "Text"]),
+    Entry::from(RdsDisplay::ProgramType, 0x01, "Program Type", &["PTY"]),
+    Entry::from(RdsDisplay::TrafficProgram, 0x02, "Traffic Program", &["TP"]),
+];
+
+static PROGRAM_TYPES: &[Entry<ProgramType, u8>] = &[
+    Entry::from(ProgramType::News, 0x01, "News", &[]),
+    Entry::from(ProgramType::CurrentAffairs, 0x02, "Current Affairs", &[]),
+    Entry::from(ProgramType::Information, 0x03, "Information", &[]),
+    Entry::from(ProgramType::Sport, 0x04, "Sport", &[]),
+    Entry::from(ProgramType::Education, 0x05, "Education", &[]),
+    Entry::from(ProgramType::Drama, 0x06, "Drama", &[]),
+    Entry::from(ProgramType::Culture, 0x07, "Culture", &[]),
+    Entry::from(ProgramType::Science, 0x08, "Science", &[]),
+    Entry::from(ProgramType::Varied, 0x09, "Varied", &[]),
+    Entry::from(ProgramType::PopMusic, 0x0A, "Pop Music", &[]),
+    Entry::from(ProgramType::RockMusic, 0x0B, "Rock Music", &[]),
+    Entry::from(ProgramType::EasyListening, 0x0C, "Easy Listening", &[]),
+    Entry::from(ProgramType::LightClassical, 0x0D, "Light Classical", &[]),
+    Entry::from(
+        ProgramType::SeriousClassical,
+        0x0E,
+        "Serious Classical",
+        &[],
+    ),
+    Entry::from(ProgramType::OtherMusic, 0x0F, "Other Music", &[]),
+    Entry::from(ProgramType::Weather, 0x10, "Weather", &[]),
+    Entry::from(ProgramType::Finance, 0x11, "Finance", &[]),
+    Entry::from(ProgramType::Children, 0x12, "Children's Programmes", &[]),
+    Entry::from(ProgramType::SocialAffairs, 0x13, "Social Affairs", &[]),
+    Entry::from(ProgramType::Religion, 0x14, "Religion", &[]),
+    Entry::from(ProgramType::PhoneIn, 0x15, "Phone-In", &[]),
+    Entry::from(ProgramType::Travel, 0x16, "Travel", &[]),
+    Entry::from(ProgramType::Leisure, 0x17, "Leisure", &[]),
+    Entry::from(ProgramType::JazzMusic, 0x18, "Jazz Music", &[]),
+    Entry::from(ProgramType::CountryMusic, 0x19, "Country Music", &[]),
+    Entry::from(ProgramType::NationalMusic, 0x1A, "National Music", &[]),
+    Entry::from(ProgramType::OldiesMusic, 0x1B, "Oldies Music", &[]),
+    Entry::from(ProgramType::FolkMusic, 0x1C, "Folk Music", &[]),
+    Entry::from(ProgramType::Documentary, 0x1D, "Documentary", &[]),
+];
+
+catalogue!(RdsDisplay, DISPLAYS, "RDS display", from_code(u8) -> code(u8));
+catalogue!(ProgramType, PROGRAM_TYPES, "program type", from_code(u8) -> code(u8));
+
+impl RdsInfo {
+    pub fn update(&mut self, message: &IscpMessage) -> bool {
+        let parameter = message.parameter.as_str();
+        let previous = self.clone();
+        match (message.command.as_str(), parameter) {
+            // The tuner answers RDS with N/A while the station carries no RDS
+            ("RDS", parameter) if parameter == ISCP_PARAM_NOT_AVAILABLE => self.reset_station(),
+            (_, parameter) if parameter == ISCP_PARAM_NOT_AVAILABLE => return false,
+            ("TUN", _) => {
+                let frequency = Frequency::from_parameter(parameter);
+                if frequency.is_some() && frequency != self.frequency {
+                    self.reset_station();
+                    self.frequency = frequency;
+                }
+            }
+            ("SLI", _) => {
+                if let Some(input) = from_hex_str(parameter).and_then(InputSource::from_code) {
+                    if Band::from_input(input).is_none() {
+                        self.reset_station();
+                        self.frequency = None;
+                    }
+                    self.input = Some(input);
+                }
+            }
+            ("RDS", _) => {
+                if let Some(display) = from_hex_str(parameter).and_then(RdsDisplay::from_code) {
+                    self.display = Some(display);
+                    self.active = true;
+                }
+            }
+            // TPS without text starts or ends a traffic program scan
+            ("TPS", "") | ("TPS", "ENTER") => {}
+            ("TPS", _) => {
+                let text = decode_display_text(parameter);
+                let text = text.trim();
+                if !text.is_empty() {
+                    self.radio_text = Some(String::from(text));
+                    self.active = true;
+                }
+            }
+            ("FLD", _) => {
+                // FLD mirrors the front panel on every input, so it only carries RDS from an
+                // RDS station on the tuner
+                if !self.active || !self.is_tuner_selected() {
+                    return false;
+                }
+                let text = decode_display_text(parameter);
+                let text = text.trim();
+                if text.is_empty() {
+                    return false;
+                }
+                match self.display {
+                    None => self.station = Some(String::from(text)),
+                    Some(RdsDisplay::ProgramType) => {
+                        if let Ok(program_type) = text.parse() {
+                            self.program_type = Some(program_type);
+                        }
+                    }
+                    Some(RdsDisplay::RadioText) | Some(RdsDisplay::TrafficProgram) => {}
+                }
+            }
+            _ => {}
+        }
+        *self != previous
+    }
+
+    fn reset_station(&mut self) {
+        *self = RdsInfo {
+            input: self.input,
+            frequency: self.frequency,
+            display: self.display,
+            ..RdsInfo::default()
+        };
+    }
+
+    fn is_tuner_selected(&self) -> bool {
+        match self.input {
+            Some(input) => Band::from_input(input).is_some(),
+            None => self.frequency.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for RdsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let station = self
+            .station
+            .clone()
+            .or_else(|| self.frequency.map(|frequency| frequency.to_string()))
+            .unwrap_or_default();
+        match &self.radio_text {
+            Some(radio_text) => write!(f, "{} \u{2013} {}", station, radio_text),
+            None => f.write_str(&station),
+        }
+    }
+}
+
+fn decode_display_text(parameter: &str) -> String {
+    match from_hex_bytes(parameter) {
+        Some(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        None => String::from(parameter),
+    }
+}
@@ -0,0 +1,87 @@
+use super::{normalize_name, Entry, Zone};
+use crate::IscpError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Speaker {
+    Front,
+    Center,
+    Surround,
+    SurroundBack,
+    Height,
+    Subwoofer,
+    Zone2,
+    Zone3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToneSetting {
+    Bass,
+    Treble,
+}
+
+static SPEAKERS: &[Entry<Speaker, &str>] = &[
+    Entry::from(Speaker::Front, "TFR", "Front", &["F"]),
+    Entry::from(Speaker::Center, "TCT", "Center", &["Centre", "C"]),
+    Entry::from(Speaker::Surround, "TSR", "Surround", &["S", "SR"]),
+    Entry::from(
+        Speaker::SurroundBack,
+        "TSB",
+        "Surround Back",
+        &["Back", "SB"],
+    ),
+    Entry::from(Speaker::Height, "THT", "Height", &["High", "H"]),
+    Entry::from(Speaker::Subwoofer, "TSW", "Subwoofer", &["Sub", "SW"]),
+    Entry::from(Speaker::Zone2, "ZTN", "Zone 2", &["Z2"]),
+    Entry::from(Speaker::Zone3, "TN3", "Zone 3", &["Z3"]),
+];
+
+catalogue!(Speaker, SPEAKERS, "speaker", from_command(&str) -> command(&'static str));
+
+impl Speaker {
+    pub fn supports(self, setting: ToneSetting) -> bool {
+        setting == ToneSetting::Bass || self != Speaker::Subwoofer
+    }
+
+    pub fn zone(self) -> Zone {
+        match self {
+            Speaker::Zone2 => Zone::Zone2,
+            Speaker::Zone3 => Zone::Zone3,
+            _ => Zone::Main,
+        }
+    }
+}
+
+impl ToneSetting {
+    pub fn prefix(self) -> char {
+        match self {
+            ToneSetting::Bass => 'B',
+            ToneSetting::Treble => 'T',
+        }
+    }
+}
+
+impl fmt::Display for ToneSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToneSetting::Bass => f.write_str("Bass"),
+            ToneSetting::Treble => f.write_str("Treble"),
+        }
+    }
+}
+
+impl FromStr for ToneSetting {
+    type Err = IscpError;
+
+    fn from_str(s: &str) -> Result<ToneSetting, IscpError> {
+        match normalize_name(s).as_str() {
+            "bass" | "b" => Ok(ToneSetting::Bass),
+            "treble" | "t" => Ok(ToneSetting::Treble),
+            _ => Err(IscpError::InvalidArgument(format!(
+                "Unknown tone setting {}",
+                s
+            ))),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use super::{
+    from_hex_str, normalize_name, InputSource, ProgramType, RdsDisplay, PRESET_MAX_NUMBER,
+};
+use crate::{Device, IscpError, Result};
+use std::fmt;
+use std::str::FromStr;
+
+const FM_MIN_KHZ: u32 = 64_000;
+const FM_MAX_KHZ: u32 = 108_000;
+const AM_MIN_KHZ: u32 = 520;
+const AM_MAX_KHZ: u32 = 1_710;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Band {
+    Fm,
+    Am,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frequency {
+    pub band: Band,
+    pub khz: u32,
+}
+
+impl Band {
+    pub fn all() -> impl Iterator<Item = Band> {
+        [Band::Fm, Band::Am].iter().copied()
+    }
+
+    pub fn input(self) -> InputSource {
+        match self {
+            Band::Fm => InputSource::Fm,
+            Band::Am => InputSource::Am,
+        }
+    }
+
+    pub fn from_input(source: InputSource) -> Option<Band> {
+        Band::all().find(|band| band.input() == source)
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Band::Fm => f.write_str("FM"),
+            Band::Am => f.write_str("AM"),
+        }
+    }
+}
+
+impl FromStr for Band {
+    type Err = IscpError;
+
+    fn from_str(s: &str) -> std::result::Result<Band, IscpError> {
+        match normalize_name(s).as_str() {
+            "fm" => Ok(Band::Fm),
+            "am" => Ok(Band::Am),
+            _ => Err(IscpError::InvalidArgument(format!("Unknown band {}", s))),
+        }
+    }
+}
+
+impl Frequency {
+    pub fn fm(mhz: f32) -> Result<Frequency> {
+        let khz = (mhz * 100.0).round() as u32 * 10;
+        if !(FM_MIN_KHZ..=FM_MAX_KHZ).contains(&khz) {
+            return Err(IscpError::InvalidArgument(format!(
+                "FM frequency {} MHz is out of range",
+                mhz
+            )));
+        }
+        Ok(Frequency {
+            band: Band::Fm,
+            khz,
+        })
+    }
+
+    pub fn am(khz: u32) -> Result<Frequency> {
+        if !(AM_MIN_KHZ..=AM_MAX_KHZ).contains(&khz) {
+            return Err(IscpError::InvalidArgument(format!(
+                "AM frequency {} kHz is out of range",
+                khz
+            )));
+        }
+        Ok(Frequency {
+            band: Band::Am,
+            khz,
+        })
+    }
+
+    pub fn from_parameter(parameter: &str) -> Option<Frequency> {
+        if parameter.len() != 5 || !parameter.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let value: u32 = parameter.parse().ok()?;
+        if value * 10 >= FM_MIN_KHZ {
+            Some(Frequency {
+                band: Band::Fm,
+                khz: value * 10,
+            })
+        } else {
+            Some(Frequency {
+                band: Band::Am,
+                khz: value,
+            })
+        }
+    }
+
+    pub fn parameter(&self) -> String {
+        match self.band {
+            Band::Fm => format!("{:05}", self.khz / 10),
+            Band::Am => format!("{:05}", self.khz),
+        }
+    }
+
+    pub fn mhz(&self) -> f32 {
+        self.khz as f32 / 1000.0
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.band {
+            Band::Fm => write!(f, "FM {}.{:02} MHz", self.khz / 1000, self.khz % 1000 / 10),
+            Band::Am => write!(f, "AM {} kHz", self.khz),
+        }
+    }
+}
+
+pub struct TunerCommands<'b> {
+    device: &'b Device,
+}
+
+impl<'b> TunerCommands<'b> {
+    pub fn from(device: &Device) -> TunerCommands<'_> {
+        TunerCommands { device }
+    }
+
+    pub fn select_band(&self, band: Band) -> Result<()> {
+        self.device
+            .raw("SLI", format!("{:02X}", band.input().code()).as_str())
+    }
+
+    pub fn tune(&self, frequency: Frequency) -> Result<()> {
+        self.select_band(frequency.band)?;
+        self.device.raw("TUN", frequency.parameter().as_str())
+    }
+
+    pub fn tune_up(&self) -> Result<()> {
+        self.device.raw("TUN", "UP")
+    }
+
+    pub fn tune_down(&self) -> Result<()> {
+        self.device.raw("TUN", "DOWN")
+    }
+
+    pub fn frequency(&self) -> Result<Frequency> {
+        let reply = self.device.query("TUN")?;
+        Frequency::from_parameter(&reply.parameter)
+            .ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    pub fn recall_preset(&self, number: u8) -> Result<()> {
+        check_preset(number)?;
+        self.device.raw("PRS", format!("{:02X}", number).as_str())
+    }
+
+    pub fn preset_up(&self) -> Result<()> {
+        self.device.raw("PRS", "UP")
+    }
+
+    pub fn preset_down(&self) -> Result<()> {
+        self.device.raw("PRS", "DOWN")
+    }
+
+    pub fn store_preset(&self, number: u8) -> Result<()> {
+        check_preset(number)?;
+        self.device.raw("PRM", format!("{:02X}", number).as_str())
+    }
+
+    pub fn preset(&self) -> Result<u8> {
+        let reply = self.device.query("PRS")?;
+        from_hex_str(&reply.parameter).ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    pub fn set_rds_display(&self, display: RdsDisplay) -> Result<()> {
+        self.device
+            .raw("RDS", format!("{:02X}", display.code()).as_str())
+    }
+
+    pub fn rds_display_up(&self) -> Result<()> {
+        self.device.raw("RDS", "UP")
+    }
+
+    pub fn start_pty_scan(&self, program_type: ProgramType) -> Result<()> {
+        self.device
+            .raw("PTS", format!("{:02X}", program_type.code()).as_str())
+    }
+
+    pub fn finish_pty_scan(&self) -> Result<()> {
+        self.device.raw("PTS", "ENTER")
+    }
+
+    pub fn start_tp_scan(&self) -> Result<()> {
+        self.device.raw("TPS", "")
+    }
+
+    pub fn finish_tp_scan(&self) -> Result<()> {
+        self.device.raw("TPS", "ENTER")
+    }
+}
+
+fn check_preset(number: u8) -> Result<()> {
+    if number == 0 || number > PRESET_MAX_NUMBER {
+        return Err(IscpError::InvalidArgument(format!(
+            "Preset {} is out of range (1 to {})",
+            number, PRESET_MAX_NUMBER
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,321 @@
+use super::{
+    check_tone_level, from_hex_str, normalize_name, parse_tone, to_signed_hex_str, InputSource,
+    PowerState, Tone, VOLUME_MAX_LEVEL,
+};
+use crate::{Device, IscpError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Zone {
+    Main,
+    Zone2,
+    Zone3,
+    Zone4,
+}
+
+impl Zone {
+    pub fn all() -> impl Iterator<Item = Zone> {
+        [Zone::Main, Zone::Zone2, Zone::Zone3, Zone::Zone4]
+            .iter()
+            .copied()
+    }
+
+    pub fn from_number(number: u8) -> Option<Zone> {
+        match number {
+            1 => Some(Zone::Main),
+            2 => Some(Zone::Zone2),
+            3 => Some(Zone::Zone3),
+            4 => Some(Zone::Zone4),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Zone::Main => 1,
+            Zone::Zone2 => 2,
+            Zone::Zone3 => 3,
+            Zone::Zone4 => 4,
+        }
+    }
+
+    pub fn power_command(self) -> &'static str {
+        match self {
+            Zone::Main => "PWR",
+            Zone::Zone2 => "ZPW",
+            Zone::Zone3 => "PW3",
+            Zone::Zone4 => "PW4",
+        }
+    }
+
+    pub fn volume_command(self) -> &'static str {
+        match self {
+            Zone::Main => "MVL",
+            Zone::Zone2 => "ZVL",
+            Zone::Zone3 => "VL3",
+            Zone::Zone4 => "VL4",
+        }
+    }
+
+    pub fn mute_command(self) -> &'static str {
+        match self {
+            Zone::Main => "AMT",
+            Zone::Zone2 => "ZMT",
+            Zone::Zone3 => "MT3",
+            Zone::Zone4 => "MT4",
+        }
+    }
+
+    pub fn input_command(self) -> &'static str {
+        match self {
+            Zone::Main => "SLI",
+            Zone::Zone2 => "SLZ",
+            Zone::Zone3 => "SL3",
+            Zone::Zone4 => "SL4",
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Main => f.write_str("Main"),
+            zone => write!(f, "Zone {}", zone.number()),
+        }
+    }
+}
+
+impl FromStr for Zone {
+    type Err = IscpError;
+
+    fn from_str(s: &str) -> std::result::Result<Zone, IscpError> {
+        let name = normalize_name(s);
+        let number = name.strip_prefix("zone").unwrap_or(&name);
+        match number {
+            "main" => Some(Zone::Main),
+            _ => number.parse().ok().and_then(Zone::from_number),
+        }
+        .ok_or_else(|| IscpError::InvalidArgument(format!("Unknown zone {}", s)))
+    }
+}
+
+pub trait ZoneCommands {
+    fn device(&self) -> &Device;
+
+    fn zone(&self) -> Zone;
+
+    fn power_off(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().power_command(), "00")
+    }
+
+    fn power_on(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().power_command(), "01")
+    }
+
+    fn power_state(&self) -> Result<PowerState> {
+        check_zone(self)?;
+        let reply = self.device().query(self.zone().power_command())?;
+        match reply.parameter.as_str() {
+            "00" => Ok(PowerState::Standby),
+            "01" => Ok(PowerState::On),
+            _ => Err(IscpError::unexpected_reply(&reply)),
+        }
+    }
+
+    fn unmute(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().mute_command(), "00")
+    }
+
+    fn mute(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().mute_command(), "01")
+    }
+
+    fn is_muted(&self) -> Result<bool> {
+        check_zone(self)?;
+        let reply = self.device().query(self.zone().mute_command())?;
+        match reply.parameter.as_str() {
+            "00" => Ok(false),
+            "01" => Ok(true),
+            _ => Err(IscpError::unexpected_reply(&reply)),
+        }
+    }
+
+    fn set_volume(&self, mut level: u8) -> Result<()> {
+        check_zone(self)?;
+        let volume_max = self
+            .device()
+            .info
+            .as_ref()
+            .and_then(|info| info.zone(self.zone()))
+            .map_or(VOLUME_MAX_LEVEL, |info| info.volume_max);
+        if level > volume_max {
+            level = volume_max;
+        }
+        self.device().raw(
+            self.zone().volume_command(),
+            format!("{:02X}", level).as_str(),
+        )
+    }
+
+    fn set_volume_up(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().volume_command(), "UP")
+    }
+
+    fn set_volume_down(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().volume_command(), "DOWN")
+    }
+
+    fn volume(&self) -> Result<u8> {
+        check_zone(self)?;
+        let reply = self.device().query(self.zone().volume_command())?;
+        from_hex_str(&reply.parameter).ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+
+    fn select_input(&self, source: InputSource) -> Result<()> {
+        check_zone(self)?;
+        if let Some(info) = self.device().info.as_ref() {
+            info.check_input(self.zone(), source)?;
+        }
+        self.device().raw(
+            self.zone().input_command(),
+            format!("{:02X}", source.code()).as_str(),
+        )
+    }
+
+    fn input_up(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().input_command(), "UP")
+    }
+
+    fn input_down(&self) -> Result<()> {
+        check_zone(self)?;
+        self.device().raw(self.zone().input_command(), "DOWN")
+    }
+
+    fn input(&self) -> Result<InputSource> {
+        check_zone(self)?;
+        let reply = self.device().query(self.zone().input_command())?;
+        from_hex_str(&reply.parameter)
+            .and_then(InputSource::from_code)
+            .ok_or_else(|| IscpError::unexpected_reply(&reply))
+    }
+}
+
+pub struct Zone2Commands<'b> {
+    device: &'b Device,
+}
+
+impl<'b> Zone2Commands<'b> {
+    pub fn from(device: &Device) -> Zone2Commands<'_> {
+        Zone2Commands { device }
+    }
+
+    pub fn set_tone_bass(&self, level: i8) -> Result<()> {
+        set_zone_tone(self, "ZTN", 'B', level)
+    }
+
+    pub fn set_tone_treble(&self, level: i8) -> Result<()> {
+        set_zone_tone(self, "ZTN", 'T', level)
+    }
+
+    pub fn tone(&self) -> Result<Tone> {
+        zone_tone(self, "ZTN")
+    }
+}
+
+impl<'b> ZoneCommands for Zone2Commands<'b> {
+    fn device(&self) -> &Device {
+        self.device
+    }
+
+    fn zone(&self) -> Zone {
+        Zone::Zone2
+    }
+}
+
+pub struct Zone3Commands<'b> {
+    device: &'b Device,
+}
+
+impl<'b> Zone3Commands<'b> {
+    pub fn from(device: &Device) -> Zone3Commands<'_> {
+        Zone3Commands { device }
+    }
+
+    pub fn set_tone_bass(&self, level: i8) -> Result<()> {
+        set_zone_tone(self, "TN3", 'B', level)
+    }
+
+    pub fn set_tone_treble(&self, level: i8) -> Result<()> {
+        set_zone_tone(self, "TN3", 'T', level)
+    }
+
+    pub fn tone(&self) -> Result<Tone> {
+        zone_tone(self, "TN3")
+    }
+}
+
+impl<'b> ZoneCommands for Zone3Commands<'b> {
+    fn device(&self) -> &Device {
+        self.device
+    }
+
+    fn zone(&self) -> Zone {
+        Zone::Zone3
+    }
+}
+
+pub struct Zone4Commands<'b> {
+    device: &'b Device,
+}
+
+impl<'b> Zone4Commands<'b> {
+    pub fn from(device: &Device) -> Zone4Commands<'_> {
+        Zone4Commands { device }
+    }
+}
+
+impl<'b> ZoneCommands for Zone4Commands<'b> {
+    fn device(&self) -> &Device {
+        self.device
+    }
+
+    fn zone(&self) -> Zone {
+        Zone::Zone4
+    }
+}
+
+fn check_zone<C: ZoneCommands + ?Sized>(commands: &C) -> Result<()> {
+    match commands.device().info.as_ref() {
+        Some(info) => info.check_zone(commands.zone()),
+        None => Ok(()),
+    }
+}
+
+fn set_zone_tone<C: ZoneCommands>(
+    commands: &C,
+    command: &str,
+    setting: char,
+    level: i8,
+) -> Result<()> {
+    check_zone(commands)?;
+    check_tone_level(level)?;
+    commands.device().raw(
+        command,
+        format!("{}{}", setting, to_signed_hex_str(level)).as_str(),
+    )
+}
+
+fn zone_tone<C: ZoneCommands>(commands: &C, command: &str) -> Result<Tone> {
+    check_zone(commands)?;
+    let reply = commands.device().query(command)?;
+    parse_tone(&reply.parameter).ok_or_else(|| IscpError::unexpected_reply(&reply))
+}
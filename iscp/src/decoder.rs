@@ -0,0 +1,141 @@
+use super::{
+    Framing, IscpMessage, ISCP_MSG_END_CR, ISCP_MSG_END_EOF, ISCP_MSG_END_LF, ISCP_MSG_HEADER_LEN,
+    ISCP_MSG_HEADER_MAGIC, ISCP_MSG_START,
+};
+use bytes::{Buf, BytesMut};
+
+const ISCP_MSG_MAX_HEADER_LEN: usize = 64;
+const ISCP_MSG_MAX_PAYLOAD_LEN: usize = 1 << 20;
+
+#[derive(Debug)]
+pub struct IscpDecoder {
+    buffer: BytesMut,
+    framing: Framing,
+}
+
+impl IscpDecoder {
+    pub fn new() -> IscpDecoder {
+        IscpDecoder::with_framing(Framing::Eiscp)
+    }
+
+    pub fn with_framing(framing: Framing) -> IscpDecoder {
+        IscpDecoder {
+            buffer: BytesMut::new(),
+            framing,
+        }
+    }
+
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    pub fn decode(&mut self) -> Option<IscpMessage> {
+        match self.framing {
+            Framing::Eiscp => decode_buffer(&mut self.buffer),
+            Framing::Iscp => decode_line_buffer(&mut self.buffer),
+        }
+    }
+
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    pub fn len_buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+pub(crate) fn decode_buffer(buffer: &mut BytesMut) -> Option<IscpMessage> {
+    loop {
+        if !sync(buffer) {
+            return None;
+        }
+
+        let len_packet = match len_packet(buffer) {
+            Some(len) => len,
+            None if buffer.len() < ISCP_MSG_HEADER_LEN as usize => return None,
+            None => {
+                buffer.advance(1);
+                continue;
+            }
+        };
+        if buffer.len() < len_packet {
+            return None;
+        }
+
+        let mut packet = buffer.split_to(len_packet).freeze();
+        if let Ok(message) = IscpMessage::from_bytes(&mut packet) {
+            return Some(message);
+        }
+    }
+}
+
+pub(crate) fn decode_line_buffer(buffer: &mut BytesMut) -> Option<IscpMessage> {
+    loop {
+        match buffer.iter().position(|b| *b == ISCP_MSG_START) {
+            Some(pos) => buffer.advance(pos),
+            None => {
+                buffer.clear();
+                return None;
+            }
+        }
+
+        let end = match buffer
+            .iter()
+            .position(|b| [ISCP_MSG_END_EOF, ISCP_MSG_END_CR, ISCP_MSG_END_LF].contains(b))
+        {
+            Some(end) => end,
+            None if buffer.len() > ISCP_MSG_MAX_PAYLOAD_LEN => {
+                buffer.clear();
+                return None;
+            }
+            None => return None,
+        };
+
+        let mut packet = buffer.split_to(end + 1).freeze();
+        if let Ok(message) = IscpMessage::from_payload(&mut packet) {
+            return Some(message);
+        }
+    }
+}
+
+fn sync(buffer: &mut BytesMut) -> bool {
+    let len_magic = ISCP_MSG_HEADER_MAGIC.len();
+    match buffer
+        .windows(len_magic)
+        .position(|window| window == ISCP_MSG_HEADER_MAGIC)
+    {
+        Some(pos) => {
+            buffer.advance(pos);
+            true
+        }
+        None => {
+            let len_keep = buffer.len().min(len_magic - 1);
+            buffer.advance(buffer.len() - len_keep);
+            false
+        }
+    }
+}
+
+fn len_packet(buffer: &BytesMut) -> Option<usize> {
+    let header = buffer.get(..ISCP_MSG_HEADER_LEN as usize)?;
+    let len_header = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let len_payload = u32::from_be_bytes([header[8], header[9], header[10], header[11]]) as usize;
+    if len_header < ISCP_MSG_HEADER_LEN as usize
+        || len_header > ISCP_MSG_MAX_HEADER_LEN
+        || len_payload > ISCP_MSG_MAX_PAYLOAD_LEN
+    {
+        return None;
+    }
+    Some(len_header + len_payload)
+}
+
+impl Default for IscpDecoder {
+    fn default() -> IscpDecoder {
+        IscpDecoder::new()
+    }
+}
@@ -1,9 +1,12 @@
 use super::commands::Commands;
-use super::IscpMessage;
+use super::{
+    AlbumArt, DeviceInfo, IscpError, IscpMessage, Link, NowPlaying, RdsInfo, ReceiverEvent,
+    ReceiverState, Result, Session, Transport,
+};
 use serde::{Deserialize, Serialize};
-use std::io::prelude::*;
-use std::io::Result;
-use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
 pub const DEVICE_MAC_MAX_LEN: usize = 12;
 
@@ -11,14 +14,24 @@ pub static DEVICE_AREA_EUROEAN_ASIA: &str = "XX";
 pub static DEVICE_AREA_NORTH_AMERICA: &str = "DX";
 pub static DEVICE_AREA_JAPANESE: &str = "JJ";
 
-#[derive(Serialize, Deserialize, Debug)]
+pub const DEVICE_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+pub const DEVICE_INFO_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub(crate) static ISCP_PARAM_QUERY: &str = "QSTN";
+pub(crate) static ISCP_PARAM_NOT_AVAILABLE: &str = "N/A";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Device {
     pub address: String,
     pub model: String,
     pub area: String,
     pub mac: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<DeviceInfo>,
     #[serde(skip)]
-    connection: Option<TcpStream>,
+    connection: Mutex<Option<Arc<Session>>>,
 }
 
 impl Device {
@@ -28,7 +41,9 @@ impl Device {
             model: String::new(),
             area: String::new(),
             mac: String::new(),
-            connection: None,
+            alias: None,
+            info: None,
+            connection: Mutex::new(None),
         }
     }
 
@@ -38,7 +53,9 @@ impl Device {
             model,
             area,
             mac,
-            connection: None,
+            alias: None,
+            info: None,
+            connection: Mutex::new(None),
         }
     }
 
@@ -48,28 +65,221 @@ impl Device {
             model: String::new(),
             area: String::new(),
             mac: String::new(),
-            connection: None,
+            alias: None,
+            info: None,
+            connection: Mutex::new(None),
         }
     }
 
-    pub fn connect(&mut self) -> Result<()> {
-        self.connection = Some(TcpStream::connect(self.address.as_str())?);
+    pub fn from_serial(path: &str, baud_rate: u32) -> Device {
+        let link = Link::Serial {
+            path: String::from(path),
+            baud_rate,
+        };
+        Device::from_address(link.to_string().as_str())
+    }
+
+    pub fn from_transport(transport: Arc<dyn Transport>) -> Device {
+        let device = Device::from_address(transport.address().as_str());
+        device.attach(transport);
+        device
+    }
+
+    pub fn attach(&self, transport: Arc<dyn Transport>) {
+        *self.connection() = Some(Arc::new(Session::open(transport)));
+    }
+
+    pub fn link(&self) -> Result<Link> {
+        self.address.parse()
+    }
+
+    pub fn identifier(&self) -> String {
+        if self.mac.is_empty() {
+            self.address.clone()
+        } else {
+            normalize_mac(&self.mac)
+        }
+    }
+
+    pub fn matches(&self, id: &str) -> bool {
+        let alias_matches = self
+            .alias
+            .as_ref()
+            .is_some_and(|alias| alias.eq_ignore_ascii_case(id));
+        alias_matches
+            || (!self.mac.is_empty() && normalize_mac(&self.mac) == normalize_mac(id))
+            || self.address == id
+    }
+
+    pub fn is_same_device(&self, other: &Device) -> bool {
+        if self.mac.is_empty() || other.mac.is_empty() {
+            self.address == other.address
+        } else {
+            normalize_mac(&self.mac) == normalize_mac(&other.mac)
+        }
+    }
+
+    pub fn connect(&self) -> Result<()> {
+        let mut connection = self.connection();
+        if connection.is_none() {
+            *connection = Some(Arc::new(Session::open(self.link()?.open()?)));
+        }
         Ok(())
     }
 
+    pub fn disconnect(&self) {
+        self.connection().take();
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection().is_some()
+    }
+
     pub fn send(&self, message: IscpMessage) -> Result<()> {
-        let mut stream = TcpStream::connect(self.address.as_str())?;
-        stream.write(message.bytes().as_ref())?;
-        Ok(())
+        if let Some(session) = self.connection().clone() {
+            return session.send(&message);
+        }
+        let transport = self.link()?.open()?;
+        let result = transport.send(&message);
+        transport.close();
+        result
+    }
+
+    pub fn receive(&self) -> Result<IscpMessage> {
+        self.session(|session| session.receive())
+    }
+
+    pub fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage> {
+        self.session(|session| session.receive_timeout(timeout))
+    }
+
+    pub fn subscribe(&self) -> Result<Receiver<IscpMessage>> {
+        self.connect()?;
+        self.session(|session| Ok(session.subscribe()))
+    }
+
+    pub fn events(&self) -> Result<Receiver<ReceiverEvent>> {
+        let (sender, receiver) = mpsc::channel();
+        self.on_event(move |event| sender.send(event).is_ok())?;
+        Ok(receiver)
     }
 
-    pub fn receive(&self) {}
+    pub fn on_event<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut(ReceiverEvent) -> bool + Send + 'static,
+    {
+        self.connect()?;
+        self.session(|session| {
+            session.add_listener(move |message| callback(ReceiverEvent::from_message(message)));
+            Ok(())
+        })
+    }
+
+    pub fn state(&self) -> Result<ReceiverState> {
+        self.connect()?;
+        self.session(|session| Ok(session.state()))
+    }
+
+    pub fn rds(&self) -> Result<RdsInfo> {
+        Ok(self.state()?.rds)
+    }
+
+    pub fn on_rds<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut(RdsInfo) -> bool + Send + 'static,
+    {
+        self.connect()?;
+        self.session(|session| {
+            let mut rds = session.state().rds;
+            session.add_listener(move |message| !rds.update(message) || callback(rds.clone()));
+            Ok(())
+        })
+    }
+
+    pub fn now_playing(&self) -> Result<NowPlaying> {
+        Ok(self.state()?.now_playing)
+    }
+
+    pub fn on_now_playing<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut(NowPlaying) -> bool + Send + 'static,
+    {
+        self.connect()?;
+        self.session(|session| {
+            let mut now_playing = session.state().now_playing;
+            session.add_listener(move |message| {
+                !now_playing.update(message) || callback(now_playing.clone())
+            });
+            Ok(())
+        })
+    }
+
+    pub fn album_art(&self) -> Result<Option<AlbumArt>> {
+        Ok(self.state()?.art.art().cloned())
+    }
 
     pub fn raw(&self, iscp_command: &str, iscp_parameter: &str) -> Result<()> {
         self.send(IscpMessage::from(iscp_command, iscp_parameter))
     }
 
-    pub fn commands(&self) -> Commands {
+    pub fn query(&self, iscp_command: &str) -> Result<IscpMessage> {
+        self.query_timeout(iscp_command, DEVICE_QUERY_TIMEOUT)
+    }
+
+    pub fn query_timeout(&self, iscp_command: &str, timeout: Duration) -> Result<IscpMessage> {
+        self.connect()?;
+        let reply = self.session(|session| session.query_timeout(iscp_command, timeout))?;
+        if reply.parameter == ISCP_PARAM_NOT_AVAILABLE {
+            return Err(IscpError::NotAvailable(String::from(iscp_command)));
+        }
+        Ok(reply)
+    }
+
+    pub fn fetch_info(&mut self) -> Result<&DeviceInfo> {
+        let reply = self.query_timeout("NRI", DEVICE_INFO_TIMEOUT)?;
+        let info = DeviceInfo::parse(&reply.parameter)?;
+        if self.model.is_empty() {
+            self.model = info.model.clone();
+        }
+        Ok(self.info.insert(info))
+    }
+
+    pub fn commands(&self) -> Commands<'_> {
         Commands::from(self)
     }
+
+    fn session<T, F: FnOnce(&Session) -> Result<T>>(&self, f: F) -> Result<T> {
+        let session = self.connection().clone();
+        match session {
+            Some(session) => f(&session),
+            None => Err(IscpError::NotConnected),
+        }
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Option<Arc<Session>>> {
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Clone for Device {
+    fn clone(&self) -> Device {
+        Device {
+            address: self.address.clone(),
+            model: self.model.clone(),
+            area: self.area.clone(),
+            mac: self.mac.clone(),
+            alias: self.alias.clone(),
+            info: self.info.clone(),
+            connection: Mutex::new(self.connection().clone()),
+        }
+    }
+}
+
+fn normalize_mac(mac: &str) -> String {
+    mac.chars()
+        .filter(char::is_ascii_hexdigit)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
 }
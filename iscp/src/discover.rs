@@ -1,73 +1,532 @@
-use std::error::Error;
-use std::net::{Ipv4Addr, UdpSocket};
-use std::time::Duration;
+use std::fmt;
+use std::io::prelude::*;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use super::{Device, IscpMessage, DEVICE_MAC_MAX_LEN, ISCP_MSG_DST_BROADCAST};
+use super::device::ISCP_PARAM_QUERY;
+use super::{
+    Device, IscpDecoder, IscpError, IscpMessage, Result, DEVICE_MAC_MAX_LEN, ISCP_MSG_DST_BROADCAST,
+};
+use log::{debug, warn};
 
 const DISCOVER_PARAMS_SEPARATOR: char = '/';
+const DISCOVER_TERMINATORS: &[char] = &['\x1a', '\r', '\n', '\0', ' '];
 
-static DISCOVER_IP: &str = "0.0.0.0";
-static DISCOVER_PORT: &str = "60128";
+pub const DISCOVER_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DISCOVER_RETRANSMISSIONS: u32 = 2;
+pub const DISCOVER_RETRANSMIT_INTERVAL: Duration = Duration::from_millis(500);
+pub const DISCOVER_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+pub const DISCOVER_PROBE_CONCURRENCY: usize = 32;
+pub const DISCOVER_MIN_PREFIX_LEN: u8 = 16;
 
-pub fn discover(duration: Duration) -> Result<Vec<Device>, Box<dyn Error>> {
-    let addr = format!("{}:{}", DISCOVER_IP, DISCOVER_PORT);
-    let socket = UdpSocket::bind(addr)?;
-    socket.set_read_timeout(Some(duration))?;
-    socket.set_broadcast(true)?;
+const DISCOVER_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
-    let discover_messsage = get_discover_message();
-    let discover_messsage_bytes = discover_messsage.bytes();
-    let broadcast_addrs = get_broadcast_addresses();
-    for broadcast_ip in broadcast_addrs {
-        let broadcast_addr = format!("{}:{}", broadcast_ip, DISCOVER_PORT);
-        println!("Sending broadcast message to: {}", broadcast_addr);
-        socket.send_to(discover_messsage_bytes.as_ref(), broadcast_addr)?;
+pub(crate) static DISCOVER_IP: &str = "0.0.0.0";
+pub(crate) const DISCOVER_PORT: u16 = 60128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryReply {
+    pub model: String,
+    pub port: u16,
+    pub area: String,
+    pub mac: String,
+    pub source: SocketAddr,
+}
+
+impl DiscoveryReply {
+    pub fn parse(data: &[u8], source: SocketAddr) -> Result<DiscoveryReply> {
+        let msg = IscpMessage::from_slice(data)?;
+        if msg.command != "ECN" {
+            return Err(malformed(format!("Unexpected command {}", msg.command)));
+        }
+        let params: Vec<&str> = msg
+            .parameter
+            .split(DISCOVER_PARAMS_SEPARATOR)
+            .map(|param| param.trim_end_matches(DISCOVER_TERMINATORS))
+            .collect();
+        let (model, port, area, mac) = match params.as_slice() {
+            [model, port, area, mac, ..] => (*model, *port, *area, *mac),
+            _ => return Err(malformed(format!("Incomplete reply {}", msg.parameter))),
+        };
+        if model.is_empty() {
+            return Err(malformed(format!("Missing model in {}", msg.parameter)));
+        }
+        let port = port
+            .parse()
+            .map_err(|_| malformed(format!("Invalid port {}", port)))?;
+        Ok(DiscoveryReply {
+            model: String::from(model),
+            port,
+            area: String::from(area),
+            mac: mac.chars().take(DEVICE_MAC_MAX_LEN).collect(),
+            source,
+        })
     }
 
-    let mut devices = Vec::new();
-    let mut buf = [0; 1024];
-    while let Ok((n, addr)) = socket.recv_from(&mut buf) {
-        if n == discover_messsage.len_bytes() {
-            continue;
-        }
-
-        if let Some(msg) = IscpMessage::from_slice(&buf[..n]) {
-            let params: Vec<&str> = msg.parameter.split(DISCOVER_PARAMS_SEPARATOR).collect();
-            let model = String::from(*params.get(0).unwrap());
-            let port = String::from(*params.get(1).unwrap());
-            let area = String::from(*params.get(2).unwrap());
-            let mac = String::from(*params.get(3).unwrap());
-            let len_mac = if mac.len() < DEVICE_MAC_MAX_LEN {
-                mac.len()
-            } else {
-                DEVICE_MAC_MAX_LEN
-            };
-            let mac = String::from(mac.get(..len_mac).unwrap());
-            let device_addr = format!("{}:{}", addr.ip(), port);
-            devices.push(Device::from(device_addr, model, area, mac));
+    pub fn address(&self) -> SocketAddr {
+        SocketAddr::new(self.source.ip(), self.port)
+    }
+
+    pub fn device(&self) -> Device {
+        Device::from(
+            self.address().to_string(),
+            self.model.clone(),
+            self.area.clone(),
+            self.mac.clone(),
+        )
+    }
+
+    fn is_same_device(&self, other: &DiscoveryReply) -> bool {
+        if self.mac.is_empty() || other.mac.is_empty() {
+            self.source.ip() == other.source.ip()
+        } else {
+            self.mac.eq_ignore_ascii_case(&other.mac)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscoverTarget {
+    Broadcast,
+    Host(String),
+    Network(Ipv4Addr, u8),
+}
+
+impl DiscoverTarget {
+    pub fn addresses(&self) -> Result<Vec<SocketAddr>> {
+        match self {
+            DiscoverTarget::Broadcast => Ok(get_broadcast_addresses()?
+                .into_iter()
+                .map(|ip| SocketAddr::new(IpAddr::V4(ip), DISCOVER_PORT))
+                .collect()),
+            DiscoverTarget::Host(host) => {
+                let addresses = if host.contains(':') {
+                    host.to_socket_addrs()
+                } else {
+                    (host.as_str(), DISCOVER_PORT).to_socket_addrs()
+                };
+                Ok(addresses
+                    .map_err(|e| {
+                        IscpError::InvalidArgument(format!("Cannot resolve {}: {}", host, e))
+                    })?
+                    .filter(SocketAddr::is_ipv4)
+                    .collect())
+            }
+            DiscoverTarget::Network(ip, prefix_len) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(*prefix_len))
+                    .unwrap_or(0);
+                let network = u32::from(*ip) & mask;
+                let broadcast = network | !mask;
+                let hosts = if *prefix_len >= 31 {
+                    network..=broadcast
+                } else {
+                    network + 1..=broadcast - 1
+                };
+                Ok(hosts
+                    .map(|host| SocketAddr::new(IpAddr::V4(Ipv4Addr::from(host)), DISCOVER_PORT))
+                    .collect())
+            }
+        }
+    }
+}
+
+impl fmt::Display for DiscoverTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoverTarget::Broadcast => f.write_str("broadcast"),
+            DiscoverTarget::Host(host) => f.write_str(host),
+            DiscoverTarget::Network(ip, prefix_len) => write!(f, "{}/{}", ip, prefix_len),
+        }
+    }
+}
+
+impl FromStr for DiscoverTarget {
+    type Err = IscpError;
+
+    fn from_str(s: &str) -> std::result::Result<DiscoverTarget, IscpError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(IscpError::InvalidArgument(String::from(
+                "Empty discovery target",
+            )));
+        }
+        if s.eq_ignore_ascii_case("broadcast") {
+            return Ok(DiscoverTarget::Broadcast);
+        }
+        let (ip, prefix_len) = match s.split_once('/') {
+            Some(network) => network,
+            None => return Ok(DiscoverTarget::Host(String::from(s))),
+        };
+        let ip = ip
+            .parse()
+            .map_err(|_| IscpError::InvalidArgument(format!("Invalid network address {}", s)))?;
+        let prefix_len = prefix_len
+            .parse()
+            .ok()
+            .filter(|prefix_len| *prefix_len <= 32)
+            .ok_or_else(|| IscpError::InvalidArgument(format!("Invalid prefix length {}", s)))?;
+        if prefix_len < DISCOVER_MIN_PREFIX_LEN {
+            return Err(IscpError::InvalidArgument(format!(
+                "Network {} is too large, use at least /{}",
+                s, DISCOVER_MIN_PREFIX_LEN
+            )));
+        }
+        Ok(DiscoverTarget::Network(ip, prefix_len))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoverOptions {
+    pub timeout: Duration,
+    pub limit: Option<usize>,
+    pub quiet_period: Option<Duration>,
+    pub retransmissions: u32,
+    pub retransmit_interval: Duration,
+    pub targets: Vec<DiscoverTarget>,
+    pub probe_tcp: bool,
+}
+
+impl DiscoverOptions {
+    pub fn new(timeout: Duration) -> DiscoverOptions {
+        DiscoverOptions {
+            timeout,
+            ..DiscoverOptions::default()
+        }
+    }
+}
+
+impl Default for DiscoverOptions {
+    fn default() -> DiscoverOptions {
+        DiscoverOptions {
+            timeout: DISCOVER_TIMEOUT,
+            limit: None,
+            quiet_period: None,
+            retransmissions: DISCOVER_RETRANSMISSIONS,
+            retransmit_interval: DISCOVER_RETRANSMIT_INTERVAL,
+            targets: vec![DiscoverTarget::Broadcast],
+            probe_tcp: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Discovery {
+    socket: UdpSocket,
+    options: DiscoverOptions,
+    message: Vec<u8>,
+    targets: Vec<SocketAddr>,
+    probes: Option<Receiver<DiscoveryReply>>,
+    probes_cancelled: Arc<AtomicBool>,
+    replies: Vec<DiscoveryReply>,
+    deadline: Instant,
+    last_reply: Instant,
+    transmissions: u32,
+    next_transmission: Instant,
+}
+
+impl Discovery {
+    pub fn start(options: DiscoverOptions) -> Result<Discovery> {
+        let addr = format!("{}:{}", DISCOVER_IP, DISCOVER_PORT);
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_broadcast(true)?;
+
+        let mut targets = Vec::new();
+        let mut probe_targets = Vec::new();
+        for target in &options.targets {
+            let addresses = target.addresses()?;
+            if options.probe_tcp && *target != DiscoverTarget::Broadcast {
+                probe_targets.extend_from_slice(&addresses);
+            }
+            targets.extend(addresses);
+        }
+        let probes_cancelled = Arc::new(AtomicBool::new(false));
+        let probes = if probe_targets.is_empty() {
+            None
+        } else {
+            let (sender, receiver) = mpsc::channel();
+            let cancelled = Arc::clone(&probes_cancelled);
+            thread::spawn(move || probe_addresses(probe_targets, sender, cancelled));
+            Some(receiver)
+        };
+
+        let now = Instant::now();
+        let mut discovery = Discovery {
+            socket,
+            message: get_discover_message().bytes()?.to_vec(),
+            targets,
+            probes,
+            probes_cancelled,
+            replies: Vec::new(),
+            deadline: now + options.timeout,
+            last_reply: now,
+            transmissions: 0,
+            next_transmission: now,
+            options,
+        };
+        discovery.transmit()?;
+        Ok(discovery)
+    }
+
+    pub fn replies(&self) -> &[DiscoveryReply] {
+        &self.replies
+    }
+
+    fn transmit(&mut self) -> Result<()> {
+        for target in &self.targets {
+            debug!("Sending discovery message to {}", target);
+            if let Err(e) = self.socket.send_to(&self.message, target) {
+                warn!("Failed to send discovery message to {}: {}", target, e);
+            }
+        }
+        self.transmissions += 1;
+        self.next_transmission += self.options.retransmit_interval;
+        Ok(())
+    }
+
+    fn wait_until(&self) -> Instant {
+        let mut until = self.deadline;
+        if let Some(quiet_period) = self.options.quiet_period {
+            until = until.min(self.last_reply + quiet_period);
+        }
+        if self.transmissions <= self.options.retransmissions {
+            until = until.min(self.next_transmission);
+        }
+        if self.probes.is_some() {
+            until = until.min(Instant::now() + DISCOVER_POLL_INTERVAL);
+        }
+        until
+    }
+
+    fn finish(&mut self) -> Option<Device> {
+        self.probes_cancelled.store(true, Ordering::SeqCst);
+        None
+    }
+
+    fn probe_reply(&mut self) -> Option<DiscoveryReply> {
+        let probes = self.probes.as_ref()?;
+        loop {
+            match probes.try_recv() {
+                Ok(reply) => {
+                    if let Some(reply) = accept_discover_reply(reply, &mut self.replies) {
+                        return Some(reply);
+                    }
+                }
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.probes = None;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        self.probes_cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Iterator for Discovery {
+    type Item = Device;
+
+    fn next(&mut self) -> Option<Device> {
+        let mut buf = [0; 1024];
+        loop {
+            if let Some(limit) = self.options.limit {
+                if self.replies.len() >= limit {
+                    return self.finish();
+                }
+            }
+            let now = Instant::now();
+            if now >= self.deadline {
+                return self.finish();
+            }
+            if let Some(quiet_period) = self.options.quiet_period {
+                if now >= self.last_reply + quiet_period {
+                    return self.finish();
+                }
+            }
+            if self.transmissions <= self.options.retransmissions && now >= self.next_transmission {
+                if let Err(e) = self.transmit() {
+                    warn!("Failed to retransmit discovery message: {}", e);
+                }
+                continue;
+            }
+            if let Some(reply) = self.probe_reply() {
+                self.last_reply = Instant::now();
+                return Some(reply.device());
+            }
+
+            let wait = self.wait_until().saturating_duration_since(now);
+            if wait == Duration::from_secs(0) || self.socket.set_read_timeout(Some(wait)).is_err() {
+                continue;
+            }
+            match self.socket.recv_from(&mut buf) {
+                Ok((n, addr)) => {
+                    if let Some(reply) = collect_discover_reply(&buf[..n], addr, &mut self.replies)
+                    {
+                        self.last_reply = Instant::now();
+                        return Some(reply.device());
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+                Err(e) => {
+                    warn!("Discovery stopped: {}", e);
+                    return self.finish();
+                }
+            }
+        }
+    }
+}
+
+pub fn discover(duration: Duration) -> Result<Vec<Device>> {
+    Ok(Discovery::start(DiscoverOptions::new(duration))?.collect())
+}
+
+pub(crate) fn collect_discover_reply(
+    data: &[u8],
+    addr: SocketAddr,
+    replies: &mut Vec<DiscoveryReply>,
+) -> Option<DiscoveryReply> {
+    if is_discover_message(data) {
+        return None;
+    }
+    let reply = match DiscoveryReply::parse(data, addr) {
+        Ok(reply) => reply,
+        Err(e) => {
+            warn!("Ignoring discovery reply from {}: {}", addr, e);
+            return None;
         }
+    };
+    accept_discover_reply(reply, replies)
+}
+
+fn accept_discover_reply(
+    reply: DiscoveryReply,
+    replies: &mut Vec<DiscoveryReply>,
+) -> Option<DiscoveryReply> {
+    if replies.iter().any(|known| known.is_same_device(&reply)) {
+        debug!("Ignoring duplicate discovery reply from {}", reply.source);
+        return None;
     }
-    Ok(devices)
+    replies.push(reply.clone());
+    Some(reply)
 }
 
-fn get_discover_message() -> IscpMessage {
+pub(crate) fn get_discover_message() -> IscpMessage {
     IscpMessage {
         destination: ISCP_MSG_DST_BROADCAST,
         command: String::from("ECN"),
-        parameter: String::from("QSTN"),
+        parameter: String::from(ISCP_PARAM_QUERY),
+    }
+}
+
+fn is_discover_message(data: &[u8]) -> bool {
+    match IscpMessage::from_slice(data) {
+        Ok(msg) => msg.command == "ECN" && msg.parameter == ISCP_PARAM_QUERY,
+        Err(_) => false,
+    }
+}
+
+fn probe_addresses(
+    addresses: Vec<SocketAddr>,
+    sender: Sender<DiscoveryReply>,
+    cancelled: Arc<AtomicBool>,
+) {
+    for chunk in addresses.chunks(DISCOVER_PROBE_CONCURRENCY) {
+        if cancelled.load(Ordering::SeqCst) {
+            debug!("Probing cancelled");
+            return;
+        }
+        let probes: Vec<_> = chunk
+            .iter()
+            .map(|addr| {
+                let addr = *addr;
+                let sender = sender.clone();
+                let cancelled = Arc::clone(&cancelled);
+                thread::spawn(move || {
+                    if let Some(reply) = probe_address(addr) {
+                        // A closed channel means the discovery is gone
+                        if sender.send(reply).is_err() {
+                            cancelled.store(true, Ordering::SeqCst);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for probe in probes {
+            let _ = probe.join();
+        }
+    }
+}
+
+fn probe_address(addr: SocketAddr) -> Option<DiscoveryReply> {
+    let mut stream = TcpStream::connect_timeout(&addr, DISCOVER_PROBE_TIMEOUT).ok()?;
+    debug!("Probing {} for its identity", addr);
+    let unknown = DiscoveryReply {
+        model: String::new(),
+        port: addr.port(),
+        area: String::new(),
+        mac: String::new(),
+        source: addr,
+    };
+    let query = IscpMessage::from("ECN", ISCP_PARAM_QUERY).bytes().ok()?;
+    if stream.write_all(query.as_ref()).is_err()
+        || stream
+            .set_read_timeout(Some(DISCOVER_PROBE_TIMEOUT))
+            .is_err()
+    {
+        return Some(unknown);
     }
+    let deadline = Instant::now() + DISCOVER_PROBE_TIMEOUT;
+    let mut decoder = IscpDecoder::new();
+    let mut buf = [0; 1024];
+    while Instant::now() < deadline {
+        let n = match stream.read(&mut buf) {
+            Ok(n) if n > 0 => n,
+            _ => break,
+        };
+        decoder.feed(&buf[..n]);
+        while let Some(message) = decoder.decode() {
+            if message.command != "ECN" {
+                continue;
+            }
+            let data = message.bytes().ok()?;
+            return match DiscoveryReply::parse(data.as_ref(), addr) {
+                Ok(reply) => Some(DiscoveryReply {
+                    port: addr.port(),
+                    ..reply
+                }),
+                Err(e) => {
+                    warn!("Ignoring probe reply from {}: {}", addr, e);
+                    Some(unknown)
+                }
+            };
+        }
+    }
+    Some(unknown)
+}
+
+fn malformed(reason: String) -> IscpError {
+    IscpError::MalformedPacket(reason)
 }
 
-fn get_broadcast_addresses() -> Vec<Ipv4Addr> {
+pub(crate) fn get_broadcast_addresses() -> Result<Vec<Ipv4Addr>> {
     let mut broadcast_addresses = Vec::new();
-    for iface in if_addrs::get_if_addrs().unwrap() {
-        match iface.addr {
-            if_addrs::IfAddr::V4(ref ifv4_addr) => match ifv4_addr.broadcast {
-                Some(addr) => broadcast_addresses.push(addr),
-                _ => {}
-            },
-            _ => {}
+    for iface in if_addrs::get_if_addrs()? {
+        if let if_addrs::IfAddr::V4(ref ifv4_addr) = iface.addr {
+            if let Some(addr) = ifv4_addr.broadcast {
+                broadcast_addresses.push(addr);
+            }
         }
     }
-    broadcast_addresses
+    Ok(broadcast_addresses)
 }
@@ -0,0 +1,75 @@
+use super::IscpMessage;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+pub type Result<T> = std::result::Result<T, IscpError>;
+
+#[derive(Debug)]
+pub enum IscpError {
+    Connect(io::Error),
+    Io(io::Error),
+    NotConnected,
+    Timeout,
+    MalformedPacket(String),
+    UnexpectedReply { command: String, parameter: String },
+    NotAvailable(String),
+    Unsupported(String),
+    InvalidArgument(String),
+    Config(String),
+}
+
+impl IscpError {
+    pub(crate) fn unexpected_reply(reply: &IscpMessage) -> IscpError {
+        IscpError::UnexpectedReply {
+            command: reply.command.clone(),
+            parameter: reply.parameter.clone(),
+        }
+    }
+}
+
+impl fmt::Display for IscpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IscpError::Connect(e) => write!(f, "Failed to connect to device: {}", e),
+            IscpError::Io(e) => write!(f, "I/O error: {}", e),
+            IscpError::NotConnected => write!(f, "Device is not connected"),
+            IscpError::Timeout => write!(f, "Timed out waiting for the device"),
+            IscpError::MalformedPacket(reason) => write!(f, "Malformed packet: {}", reason),
+            IscpError::UnexpectedReply { command, parameter } => {
+                write!(f, "Unexpected reply: {}{}", command, parameter)
+            }
+            IscpError::NotAvailable(command) => {
+                write!(f, "{} is not available on this device", command)
+            }
+            IscpError::Unsupported(reason) => write!(f, "Unsupported: {}", reason),
+            IscpError::InvalidArgument(reason) => write!(f, "Invalid argument: {}", reason),
+            IscpError::Config(reason) => write!(f, "Invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl Error for IscpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IscpError::Connect(e) | IscpError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for IscpError {
+    fn from(e: io::Error) -> IscpError {
+        match e.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => IscpError::Timeout,
+            io::ErrorKind::NotConnected => IscpError::NotConnected,
+            _ => IscpError::Io(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for IscpError {
+    fn from(e: serde_json::Error) -> IscpError {
+        IscpError::Config(e.to_string())
+    }
+}
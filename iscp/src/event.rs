@@ -0,0 +1,99 @@
+use super::commands::{from_hex_str, from_signed_hex_str, parse_tone};
+use super::{
+    DspFeature, DspLevel, Frequency, InputSource, IscpMessage, LevelChannel, ListeningMode,
+    PowerState, Speaker, Tone, Zone,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReceiverEvent {
+    Power {
+        zone: Zone,
+        state: PowerState,
+    },
+    Volume {
+        zone: Zone,
+        level: u8,
+    },
+    Mute {
+        zone: Zone,
+        muted: bool,
+    },
+    Input {
+        zone: Zone,
+        source: InputSource,
+    },
+    ListeningMode(ListeningMode),
+    SpeakerTone {
+        speaker: Speaker,
+        tone: Tone,
+    },
+    ChannelLevel {
+        channel: LevelChannel,
+        level: i8,
+    },
+    Dsp {
+        feature: DspFeature,
+        level: DspLevel,
+    },
+    Tuner(Frequency),
+    Preset(u8),
+    Other(IscpMessage),
+}
+
+impl ReceiverEvent {
+    pub fn from_message(message: &IscpMessage) -> ReceiverEvent {
+        ReceiverEvent::decode(message).unwrap_or_else(|| ReceiverEvent::Other(message.clone()))
+    }
+
+    fn decode(message: &IscpMessage) -> Option<ReceiverEvent> {
+        let command = message.command.as_str();
+        let parameter = message.parameter.as_str();
+        for zone in Zone::all() {
+            if command == zone.power_command() {
+                let state = match parameter {
+                    "00" => PowerState::Standby,
+                    "01" => PowerState::On,
+                    _ => return None,
+                };
+                return Some(ReceiverEvent::Power { zone, state });
+            }
+            if command == zone.volume_command() {
+                let level = from_hex_str(parameter)?;
+                return Some(ReceiverEvent::Volume { zone, level });
+            }
+            if command == zone.mute_command() {
+                let muted = match parameter {
+                    "00" => false,
+                    "01" => true,
+                    _ => return None,
+                };
+                return Some(ReceiverEvent::Mute { zone, muted });
+            }
+            if command == zone.input_command() {
+                let source = from_hex_str(parameter).and_then(InputSource::from_code)?;
+                return Some(ReceiverEvent::Input { zone, source });
+            }
+        }
+        match command {
+            "LMD" => from_hex_str(parameter)
+                .and_then(ListeningMode::from_code)
+                .map(ReceiverEvent::ListeningMode),
+            "TUN" => Frequency::from_parameter(parameter).map(ReceiverEvent::Tuner),
+            "PRS" => from_hex_str(parameter).map(ReceiverEvent::Preset),
+            _ => {
+                if let Some(channel) = LevelChannel::from_command(command) {
+                    let level = from_signed_hex_str(parameter)?;
+                    return Some(ReceiverEvent::ChannelLevel { channel, level });
+                }
+                if let Some(feature) = DspFeature::from_command(command) {
+                    let level =
+                        from_hex_str(parameter).and_then(|code| feature.level_from_code(code))?;
+                    return Some(ReceiverEvent::Dsp { feature, level });
+                }
+                let speaker = Speaker::from_command(command)?;
+                let tone = parse_tone(parameter)?;
+                Some(ReceiverEvent::SpeakerTone { speaker, tone })
+            }
+        }
+    }
+}
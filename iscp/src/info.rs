@@ -0,0 +1,202 @@
+use super::{InputSource, IscpError, Result, Zone, VOLUME_MAX_LEVEL};
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DeviceInfo {
+    pub brand: String,
+    pub model: String,
+    pub firmware_version: String,
+    pub zones: Vec<ZoneInfo>,
+    pub selectors: Vec<SelectorInfo>,
+    pub presets: Vec<PresetInfo>,
+    pub controls: Vec<ControlInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ZoneInfo {
+    pub zone: Zone,
+    pub name: String,
+    pub volume_max: u8,
+    pub volume_step: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SelectorInfo {
+    pub code: u8,
+    pub name: String,
+    pub zones: Vec<Zone>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PresetInfo {
+    pub number: u8,
+    pub band: String,
+    pub frequency: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ControlInfo {
+    pub id: String,
+    pub zone: Option<Zone>,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    pub step: Option<i32>,
+}
+
+impl DeviceInfo {
+    pub fn parse(xml: &str) -> Result<DeviceInfo> {
+        let document = Document::parse(xml)
+            .map_err(|e| IscpError::MalformedPacket(format!("Invalid NRI XML: {}", e)))?;
+        let response = document.root_element();
+        if response.attribute("status").unwrap_or("ok") != "ok" {
+            return Err(IscpError::NotAvailable(String::from("NRI")));
+        }
+        let device = child(response, "device")
+            .ok_or_else(|| IscpError::MalformedPacket(String::from("NRI XML without device")))?;
+
+        Ok(DeviceInfo {
+            brand: child_text(device, "brand"),
+            model: child_text(device, "model"),
+            firmware_version: child_text(device, "firmwareversion"),
+            zones: enabled_items(device, "zonelist")
+                .filter_map(|node| {
+                    let zone = node
+                        .attribute("id")?
+                        .parse()
+                        .ok()
+                        .and_then(Zone::from_number)?;
+                    Some(ZoneInfo {
+                        zone,
+                        name: String::from(node.attribute("name").unwrap_or_default()),
+                        volume_max: node
+                            .attribute("volmax")
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(VOLUME_MAX_LEVEL),
+                        volume_step: match node.attribute("volstep") {
+                            Some("1") => 0.5,
+                            _ => 1.0,
+                        },
+                    })
+                })
+                .collect(),
+            selectors: enabled_items(device, "selectorlist")
+                .filter_map(|node| {
+                    Some(SelectorInfo {
+                        code: u8::from_str_radix(node.attribute("id")?, 16).ok()?,
+                        name: String::from(node.attribute("name").unwrap_or_default()),
+                        zones: zones_from_mask(node.attribute("zone").unwrap_or("01")),
+                    })
+                })
+                .collect(),
+            presets: items(device, "presetlist")
+                .filter(|node| node.attribute("band").unwrap_or("0") != "0")
+                .filter_map(|node| {
+                    Some(PresetInfo {
+                        number: u8::from_str_radix(node.attribute("id")?, 16).ok()?,
+                        band: match node.attribute("band")? {
+                            "1" => String::from("FM"),
+                            "2" => String::from("AM"),
+                            band => String::from(band),
+                        },
+                        frequency: String::from(node.attribute("freq").unwrap_or_default()),
+                        name: String::from(node.attribute("name").unwrap_or_default()),
+                    })
+                })
+                .collect(),
+            controls: enabled_items(device, "controllist")
+                .filter_map(|node| {
+                    Some(ControlInfo {
+                        id: String::from(node.attribute("id")?),
+                        zone: node
+                            .attribute("zone")
+                            .and_then(|v| v.parse().ok())
+                            .and_then(Zone::from_number),
+                        min: node.attribute("min").and_then(|v| v.parse().ok()),
+                        max: node.attribute("max").and_then(|v| v.parse().ok()),
+                        step: node.attribute("step").and_then(|v| v.parse().ok()),
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    pub fn zone(&self, zone: Zone) -> Option<&ZoneInfo> {
+        self.zones.iter().find(|info| info.zone == zone)
+    }
+
+    pub fn supports_zone(&self, zone: Zone) -> bool {
+        self.zones.is_empty() || self.zone(zone).is_some()
+    }
+
+    pub fn supports_input(&self, zone: Zone, source: InputSource) -> bool {
+        self.selectors.is_empty()
+            || self
+                .selectors
+                .iter()
+                .any(|selector| selector.code == source.code() && selector.zones.contains(&zone))
+    }
+
+    pub fn control(&self, id: &str) -> Option<&ControlInfo> {
+        self.controls
+            .iter()
+            .find(|control| control.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn check_zone(&self, zone: Zone) -> Result<()> {
+        if self.supports_zone(zone) {
+            Ok(())
+        } else {
+            Err(IscpError::Unsupported(format!(
+                "{} does not support {}",
+                self.model, zone
+            )))
+        }
+    }
+
+    pub fn check_input(&self, zone: Zone, source: InputSource) -> Result<()> {
+        if self.supports_input(zone, source) {
+            Ok(())
+        } else {
+            Err(IscpError::Unsupported(format!(
+                "{} does not support input {} in {}",
+                self.model, source, zone
+            )))
+        }
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text(node: Node, name: &str) -> String {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| String::from(text.trim()))
+        .unwrap_or_default()
+}
+
+fn items<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    list: &str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    child(node, list)
+        .into_iter()
+        .flat_map(|list| list.children().filter(|item| item.is_element()))
+}
+
+fn enabled_items<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    list: &str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    items(node, list).filter(|item| item.attribute("value").unwrap_or("1") == "1")
+}
+
+fn zones_from_mask(mask: &str) -> Vec<Zone> {
+    let mask = u8::from_str_radix(mask, 16).unwrap_or(0);
+    Zone::all()
+        .filter(|zone| mask & (1 << (zone.number() - 1)) != 0)
+        .collect()
+}
@@ -1,11 +1,31 @@
+#[cfg(feature = "async")]
+mod asynchronous;
 mod commands;
+mod decoder;
 mod device;
 mod discover;
+mod error;
+mod event;
+mod info;
 mod message;
+mod mock;
 mod remote;
+mod session;
+mod state;
+mod transport;
 
+#[cfg(feature = "async")]
+pub use asynchronous::*;
 pub use commands::*;
+pub use decoder::*;
 pub use device::*;
 pub use discover::*;
+pub use error::*;
+pub use event::*;
+pub use info::*;
 pub use message::*;
+pub use mock::*;
 pub use remote::*;
+pub use session::*;
+pub use state::*;
+pub use transport::*;
@@ -1,19 +1,29 @@
+use super::{IscpError, Result};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 pub const ISCP_MSG_DST_RECEIVER: u8 = b'1';
 pub const ISCP_MSG_DST_BROADCAST: u8 = b'x';
 
 const ISCP_VERSION: u8 = 0x01;
-const ISCP_MSG_START: u8 = b'!';
-const ISCP_MSG_END_LF: u8 = 0x0A;
-const ISCP_MSG_HEADER_LEN: u32 = 16;
+pub(crate) const ISCP_MSG_START: u8 = b'!';
+pub(crate) const ISCP_MSG_END_EOF: u8 = 0x1A;
+pub(crate) const ISCP_MSG_END_CR: u8 = 0x0D;
+pub(crate) const ISCP_MSG_END_LF: u8 = 0x0A;
+pub(crate) const ISCP_MSG_HEADER_LEN: u32 = 16;
 const ISCP_MSG_CMD_LEN: usize = 3;
-const ISCP_MSG_MIN_LEN: usize = ISCP_MSG_HEADER_LEN as usize + 2 + ISCP_MSG_CMD_LEN + 1;
+const ISCP_MSG_MIN_PAYLOAD_LEN: usize = 2 + ISCP_MSG_CMD_LEN;
+const ISCP_MSG_MIN_LEN: usize = ISCP_MSG_HEADER_LEN as usize + ISCP_MSG_MIN_PAYLOAD_LEN + 1;
 
-static ISCP_MSG_HEADER_MAGIC: &[u8] = b"ISCP";
+pub(crate) static ISCP_MSG_HEADER_MAGIC: &[u8] = b"ISCP";
 static ISCP_MSG_HEADER_RESERVED: &[u8] = &[0x00; 3];
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    Eiscp,
+    Iscp,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct IscpMessage {
     pub destination: u8,
     pub command: String,
@@ -37,53 +47,87 @@ impl IscpMessage {
         }
     }
 
-    pub fn from_slice(data: &[u8]) -> Option<IscpMessage> {
+    pub fn from_slice(data: &[u8]) -> Result<IscpMessage> {
         let mut packet = Bytes::copy_from_slice(data);
         IscpMessage::from_bytes(&mut packet)
     }
 
-    pub fn from_bytes(packet: &mut Bytes) -> Option<IscpMessage> {
+    pub fn from_bytes(packet: &mut Bytes) -> Result<IscpMessage> {
         if packet.len() < ISCP_MSG_MIN_LEN {
-            return None;
+            return Err(malformed("Packet too short"));
         }
 
         let len_magic = ISCP_MSG_HEADER_MAGIC.len();
-        if packet.get(..len_magic)? != ISCP_MSG_HEADER_MAGIC {
-            return None;
+        if packet.get(..len_magic) != Some(ISCP_MSG_HEADER_MAGIC) {
+            return Err(malformed("Missing ISCP header"));
         }
         packet.advance(len_magic);
-        let _len_header = packet.get_u32();
-        let _len_payload = packet.get_u32();
+        let len_header = packet.get_u32();
+        let len_payload = packet.get_u32() as usize;
         let _version = packet.get_u8();
         packet.advance(ISCP_MSG_HEADER_RESERVED.len());
+        if len_header < ISCP_MSG_HEADER_LEN {
+            return Err(malformed("Invalid header length"));
+        }
+        let len_extra_header = (len_header - ISCP_MSG_HEADER_LEN) as usize;
+        if packet.len() < len_extra_header + len_payload || len_payload < ISCP_MSG_MIN_PAYLOAD_LEN {
+            return Err(malformed("Invalid payload length"));
+        }
+        packet.advance(len_extra_header);
+        packet.truncate(len_payload);
+        IscpMessage::from_payload(packet)
+    }
+
+    pub fn from_payload(packet: &mut Bytes) -> Result<IscpMessage> {
+        if packet.len() < ISCP_MSG_MIN_PAYLOAD_LEN {
+            return Err(malformed("Payload too short"));
+        }
         if packet.get_u8() != ISCP_MSG_START {
-            return None;
+            return Err(malformed("Missing start character"));
         }
         let destination = packet.get_u8();
-        let command = String::from_utf8(packet.get(..ISCP_MSG_CMD_LEN).unwrap().to_vec()).unwrap();
-        packet.advance(ISCP_MSG_CMD_LEN);
-        let parameter = String::from_utf8(packet.get(..).unwrap().to_vec()).unwrap();
+        let command = String::from_utf8(packet.split_to(ISCP_MSG_CMD_LEN).to_vec())
+            .map_err(|_| malformed("Command is not valid UTF-8"))?;
+        let end = packet
+            .iter()
+            .rposition(|b| ![ISCP_MSG_END_EOF, ISCP_MSG_END_CR, ISCP_MSG_END_LF].contains(b))
+            .map_or(0, |i| i + 1);
+        let parameter = String::from_utf8(packet.split_to(end).to_vec())
+            .map_err(|_| malformed("Parameter is not valid UTF-8"))?;
 
-        Some(IscpMessage {
+        Ok(IscpMessage {
             destination,
             command,
             parameter,
         })
     }
 
-    pub fn bytes(&self) -> BytesMut {
+    pub fn bytes(&self) -> Result<BytesMut> {
+        self.encode(Framing::Eiscp)
+    }
+
+    pub fn encode(&self, framing: Framing) -> Result<BytesMut> {
+        let command = self
+            .command
+            .get(..ISCP_MSG_CMD_LEN)
+            .ok_or_else(|| malformed("Command must have 3 characters"))?;
         let mut bytes = BytesMut::with_capacity(self.len_bytes());
-        bytes.put_slice(ISCP_MSG_HEADER_MAGIC);
-        bytes.put_u32(ISCP_MSG_HEADER_LEN);
-        bytes.put_u32(self.len_payload() as u32);
-        bytes.put_u8(ISCP_VERSION);
-        bytes.put_slice(ISCP_MSG_HEADER_RESERVED);
+        if framing == Framing::Eiscp {
+            bytes.put_slice(ISCP_MSG_HEADER_MAGIC);
+            bytes.put_u32(ISCP_MSG_HEADER_LEN);
+            bytes.put_u32(self.len_payload() as u32);
+            bytes.put_u8(ISCP_VERSION);
+            bytes.put_slice(ISCP_MSG_HEADER_RESERVED);
+        }
         bytes.put_u8(ISCP_MSG_START);
         bytes.put_u8(self.destination);
-        bytes.put_slice(self.command.get(..ISCP_MSG_CMD_LEN).unwrap().as_bytes());
+        bytes.put_slice(command.as_bytes());
         bytes.put_slice(self.parameter.as_bytes());
-        bytes.put_u8(ISCP_MSG_END_LF);
-        bytes
+        bytes.put_u8(match framing {
+            Framing::Eiscp => ISCP_MSG_END_LF,
+            Framing::Iscp => ISCP_MSG_END_CR,
+        });
+        Ok(bytes)
     }
 
     pub fn len_payload(&self) -> usize {
@@ -94,3 +138,13 @@ impl IscpMessage {
         ISCP_MSG_HEADER_LEN as usize + self.len_payload()
     }
 }
+
+impl Default for IscpMessage {
+    fn default() -> IscpMessage {
+        IscpMessage::new()
+    }
+}
+
+fn malformed(reason: &str) -> IscpError {
+    IscpError::MalformedPacket(String::from(reason))
+}
@@ -0,0 +1,437 @@
+use super::commands::{from_hex_str, from_signed_hex_str, parse_tone, to_signed_hex_str};
+use super::device::{ISCP_PARAM_NOT_AVAILABLE, ISCP_PARAM_QUERY};
+use super::{
+    Device, DspFeature, DspLevel, InputSource, IscpDecoder, IscpError, IscpMessage, LevelChannel,
+    ListeningMode, PowerState, Result, Speaker, Tone, ToneSetting, Zone, TONE_MAX_LEVEL,
+    TONE_MIN_LEVEL, VOLUME_MAX_LEVEL,
+};
+use std::collections::HashMap;
+use std::io::prelude::*;
+use std::mem;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const MOCK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub struct MockReceiver {
+    address: SocketAddr,
+    discovery_address: Option<SocketAddr>,
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+    port: u16,
+    model: String,
+    area: String,
+    mac: String,
+    state: Mutex<HashMap<String, String>>,
+    received: Mutex<Vec<IscpMessage>>,
+    clients: Mutex<Vec<TcpStream>>,
+    running: AtomicBool,
+}
+
+enum Reply {
+    Sender(IscpMessage),
+    Broadcast(IscpMessage),
+}
+
+impl MockReceiver {
+    pub fn start(device: &Device, discovery_address: Option<&str>) -> Result<MockReceiver> {
+        let listener = TcpListener::bind(device.address.as_str()).map_err(IscpError::Connect)?;
+        let address = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            port: address.port(),
+            model: device.model.clone(),
+            area: device.area.clone(),
+            mac: device.mac.clone(),
+            state: Mutex::new(initial_state()),
+            received: Mutex::new(Vec::new()),
+            clients: Mutex::new(Vec::new()),
+            running: AtomicBool::new(true),
+        });
+
+        let mut threads = Vec::new();
+        let accept_shared = Arc::clone(&shared);
+        threads.push(thread::spawn(move || {
+            accept_clients(listener, accept_shared)
+        }));
+
+        let discovery_address = match discovery_address {
+            Some(discovery_address) => {
+                let socket = UdpSocket::bind(discovery_address).map_err(IscpError::Connect)?;
+                socket.set_read_timeout(Some(MOCK_POLL_INTERVAL))?;
+                let discovery_address = socket.local_addr()?;
+                let discovery_shared = Arc::clone(&shared);
+                threads.push(thread::spawn(move || {
+                    answer_discovery(socket, discovery_shared)
+                }));
+                Some(discovery_address)
+            }
+            None => None,
+        };
+
+        Ok(MockReceiver {
+            address,
+            discovery_address,
+            shared,
+            threads,
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn discovery_address(&self) -> Option<SocketAddr> {
+        self.discovery_address
+    }
+
+    pub fn device(&self) -> Device {
+        Device::from(
+            self.address.to_string(),
+            self.shared.model.clone(),
+            self.shared.area.clone(),
+            self.shared.mac.clone(),
+        )
+    }
+
+    pub fn get(&self, command: &str) -> Option<String> {
+        lock(&self.shared.state).get(command).cloned()
+    }
+
+    pub fn set(&self, command: &str, parameter: &str) {
+        lock(&self.shared.state).insert(String::from(command), String::from(parameter));
+        broadcast(&self.shared, &IscpMessage::from(command, parameter));
+    }
+
+    pub fn received(&self) -> Vec<IscpMessage> {
+        lock(&self.shared.received).clone()
+    }
+
+    pub fn take_received(&self) -> Vec<IscpMessage> {
+        mem::take(&mut *lock(&self.shared.received))
+    }
+
+    pub fn clear_received(&self) {
+        lock(&self.shared.received).clear();
+    }
+
+    pub fn power(&self, zone: Zone) -> Option<PowerState> {
+        match self.get(zone.power_command())?.as_str() {
+            "00" => Some(PowerState::Standby),
+            "01" => Some(PowerState::On),
+            _ => None,
+        }
+    }
+
+    pub fn volume(&self, zone: Zone) -> Option<u8> {
+        from_hex_str(&self.get(zone.volume_command())?)
+    }
+
+    pub fn is_muted(&self, zone: Zone) -> Option<bool> {
+        Some(self.get(zone.mute_command())? == "01")
+    }
+
+    pub fn input(&self, zone: Zone) -> Option<InputSource> {
+        from_hex_str(&self.get(zone.input_command())?).and_then(InputSource::from_code)
+    }
+
+    pub fn listening_mode(&self) -> Option<ListeningMode> {
+        from_hex_str(&self.get("LMD")?).and_then(ListeningMode::from_code)
+    }
+
+    pub fn tone(&self, speaker: Speaker) -> Option<Tone> {
+        parse_tone(&self.get(speaker.command())?)
+    }
+
+    pub fn tone_front(&self) -> Option<Tone> {
+        self.tone(Speaker::Front)
+    }
+
+    pub fn channel_level(&self, channel: LevelChannel) -> Option<i8> {
+        from_signed_hex_str(&self.get(channel.command())?)
+    }
+
+    pub fn dsp(&self, feature: DspFeature) -> Option<DspLevel> {
+        from_hex_str(&self.get(feature.command())?).and_then(|code| feature.level_from_code(code))
+    }
+}
+
+impl Drop for MockReceiver {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+        for client in lock(&self.shared.clients).drain(..) {
+            let _ = client.shutdown(Shutdown::Both);
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn initial_state() -> HashMap<String, String> {
+    let mut state = HashMap::new();
+    for zone in Zone::all() {
+        state.insert(String::from(zone.power_command()), String::from("00"));
+        state.insert(String::from(zone.volume_command()), String::from("14"));
+        state.insert(String::from(zone.mute_command()), String::from("00"));
+        state.insert(String::from(zone.input_command()), String::from("10"));
+    }
+    state.insert(String::from("LMD"), String::from("00"));
+    state.insert(String::from("TUN"), String::from("08750"));
+    state.insert(String::from("PRS"), String::from("01"));
+    for speaker in Speaker::all() {
+        state.insert(
+            String::from(speaker.command()),
+            format_tone(speaker, Tone { bass: 0, treble: 0 }),
+        );
+    }
+    for channel in LevelChannel::all() {
+        state.insert(String::from(channel.command()), to_signed_hex_str(0));
+    }
+    for feature in DspFeature::all() {
+        state.insert(String::from(feature.command()), String::from("00"));
+    }
+    state
+}
+
+fn accept_clients(listener: TcpListener, shared: Arc<Shared>) {
+    let mut clients = Vec::new();
+    for stream in listener.incoming() {
+        if !shared.running.load(Ordering::SeqCst) {
+            break;
+        }
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        if let Ok(client) = stream.try_clone() {
+            lock(&shared.clients).push(client);
+        }
+        let client_shared = Arc::clone(&shared);
+        clients.push(thread::spawn(move || serve_client(stream, client_shared)));
+    }
+    for client in clients {
+        let _ = client.join();
+    }
+}
+
+fn serve_client(mut stream: TcpStream, shared: Arc<Shared>) {
+    let mut decoder = IscpDecoder::new();
+    let mut buf = [0; 1024];
+    while let Ok(n) = stream.read(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        decoder.feed(&buf[..n]);
+        while let Some(message) = decoder.decode() {
+            lock(&shared.received).push(message.clone());
+            match handle_message(&shared, &message) {
+                Reply::Sender(reply) => {
+                    if let Ok(bytes) = reply.bytes() {
+                        let _ = stream.write_all(bytes.as_ref());
+                    }
+                }
+                Reply::Broadcast(reply) => broadcast(&shared, &reply),
+            }
+        }
+    }
+}
+
+fn answer_discovery(socket: UdpSocket, shared: Arc<Shared>) {
+    let mut buf = [0; 1024];
+    while shared.running.load(Ordering::SeqCst) {
+        let (n, addr) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(_) => continue,
+        };
+        let message = match IscpMessage::from_slice(&buf[..n]) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+        if message.command != "ECN" || message.parameter != ISCP_PARAM_QUERY {
+            continue;
+        }
+        let reply = IscpMessage::from("ECN", identity(&shared).as_str());
+        if let Ok(bytes) = reply.bytes() {
+            let _ = socket.send_to(bytes.as_ref(), addr);
+        }
+    }
+}
+
+fn handle_message(shared: &Shared, message: &IscpMessage) -> Reply {
+    let command = message.command.as_str();
+    let parameter = message.parameter.as_str();
+    let mut state = lock(&shared.state);
+
+    if parameter == ISCP_PARAM_QUERY {
+        let value = match command {
+            "ECN" => identity(shared),
+            "NRI" => info_xml(shared),
+            _ => state
+                .get(command)
+                .cloned()
+                .unwrap_or_else(|| String::from(ISCP_PARAM_NOT_AVAILABLE)),
+        };
+        return Reply::Sender(IscpMessage::from(command, value.as_str()));
+    }
+
+    if command == "NJA" && parameter == "REQ" {
+        return Reply::Sender(IscpMessage::from(command, "n-"));
+    }
+
+    let current = state.get(command).cloned().unwrap_or_default();
+    let value = if let Some(speaker) = Speaker::from_command(command) {
+        update_tone(speaker, &current, parameter)
+    } else if let Some(channel) = LevelChannel::from_command(command) {
+        update_level(channel, &current, parameter)
+    } else if let Some(feature) = DspFeature::from_command(command) {
+        update_dsp(feature, &current, parameter)
+    } else if Zone::all().any(|zone| zone.volume_command() == command) {
+        update_volume(&current, parameter)
+    } else if Zone::all().any(|zone| zone.mute_command() == command) && parameter == "TG" {
+        String::from(if current == "01" { "00" } else { "01" })
+    } else if parameter == "UP" || parameter == "DOWN" {
+        current
+    } else {
+        String::from(parameter)
+    };
+    state.insert(String::from(command), value.clone());
+    Reply::Broadcast(IscpMessage::from(command, value.as_str()))
+}
+
+fn update_volume(current: &str, parameter: &str) -> String {
+    let level = from_hex_str(current).unwrap_or(0);
+    let level = match parameter {
+        "UP" | "UP1" => level.saturating_add(1).min(VOLUME_MAX_LEVEL),
+        "DOWN" | "DOWN1" => level.saturating_sub(1),
+        _ => from_hex_str(parameter)
+            .unwrap_or(level)
+            .min(VOLUME_MAX_LEVEL),
+    };
+    format!("{:02X}", level)
+}
+
+fn update_tone(speaker: Speaker, current: &str, parameter: &str) -> String {
+    let tone = parse_tone(current).unwrap_or(Tone { bass: 0, treble: 0 });
+    let (setting, value) = parameter.split_at(parameter.len().min(1));
+    let level = if setting == "B" {
+        tone.bass
+    } else {
+        tone.treble
+    };
+    let level = match value {
+        "UP" => level + 1,
+        "DOWN" => level - 1,
+        _ => from_signed_hex_str(value).unwrap_or(level),
+    }
+    .clamp(TONE_MIN_LEVEL, TONE_MAX_LEVEL);
+    let tone = match setting {
+        "B" => Tone {
+            bass: level,
+            ..tone
+        },
+        "T" => Tone {
+            treble: level,
+            ..tone
+        },
+        _ => tone,
+    };
+    format_tone(speaker, tone)
+}
+
+fn update_level(channel: LevelChannel, current: &str, parameter: &str) -> String {
+    let level = from_signed_hex_str(current).unwrap_or(0);
+    let level = match parameter {
+        "UP" => level + 1,
+        "DOWN" => level - 1,
+        _ => from_signed_hex_str(parameter).unwrap_or(level),
+    }
+    .clamp(channel.min_level(), channel.max_level());
+    to_signed_hex_str(level)
+}
+
+fn update_dsp(feature: DspFeature, current: &str, parameter: &str) -> String {
+    let levels: Vec<DspLevel> = feature.levels().collect();
+    let level = from_hex_str(current)
+        .and_then(|code| feature.level_from_code(code))
+        .unwrap_or(DspLevel::Off);
+    let level = if parameter == feature.cycle_parameter() {
+        let position = levels.iter().position(|l| *l == level).unwrap_or(0);
+        levels[(position + 1) % levels.len()]
+    } else {
+        from_hex_str(parameter)
+            .and_then(|code| feature.level_from_code(code))
+            .unwrap_or(level)
+    };
+    format!("{:02X}", feature.level_code(level).unwrap_or(0))
+}
+
+fn format_tone(speaker: Speaker, tone: Tone) -> String {
+    if speaker.supports(ToneSetting::Treble) {
+        format!(
+            "B{}T{}",
+            to_signed_hex_str(tone.bass),
+            to_signed_hex_str(tone.treble)
+        )
+    } else {
+        format!("B{}", to_signed_hex_str(tone.bass))
+    }
+}
+
+fn identity(shared: &Shared) -> String {
+    format!(
+        "{}/{}/{}/{}",
+        shared.model, shared.port, shared.area, shared.mac
+    )
+}
+
+fn info_xml(shared: &Shared) -> String {
+    let zones: String = Zone::all()
+        .map(|zone| {
+            format!(
+                "<zone id=\"{}\" value=\"1\" name=\"{}\" volmax=\"{}\" volstep=\"0\"/>",
+                zone.number(),
+                zone,
+                VOLUME_MAX_LEVEL
+            )
+        })
+        .collect();
+    let selectors: String = InputSource::all()
+        .map(|source| {
+            format!(
+                "<selector id=\"{:02X}\" value=\"1\" name=\"{}\" zone=\"0F\"/>",
+                source.code(),
+                source
+            )
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?><response status=\"ok\"><device id=\"{model}\">\
+         <brand>ONKYO</brand><model>{model}</model><firmwareversion>sim</firmwareversion>\
+         <zonelist count=\"4\">{zones}</zonelist><selectorlist>{selectors}</selectorlist>\
+         </device></response>",
+        model = shared.model,
+        zones = zones,
+        selectors = selectors
+    )
+}
+
+fn broadcast(shared: &Shared, message: &IscpMessage) {
+    let bytes = match message.bytes() {
+        Ok(bytes) => bytes,
+        Err(_) => return,
+    };
+    lock(&shared.clients).retain(|mut client| client.write_all(bytes.as_ref()).is_ok());
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -1,12 +1,12 @@
-use super::{discover, Device};
+use super::{discover, Device, DiscoverOptions, Discovery, IscpError, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::Result;
+use std::io::ErrorKind;
 use std::time::Duration;
 
 static CONFIG_FILE_NAME: &str = "remote.json";
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Remote {
     pub devices: Vec<Device>,
 }
@@ -18,20 +18,20 @@ impl Remote {
         }
     }
 
-    pub fn load() -> Remote {
+    pub fn load() -> Result<Remote> {
         Remote::load_from(CONFIG_FILE_NAME)
     }
 
-    pub fn load_from(file: &str) -> Remote {
-        if let Ok(json) = fs::read_to_string(file) {
-            Remote::deserialize(json.as_str())
-        } else {
-            Remote::new()
+    pub fn load_from(file: &str) -> Result<Remote> {
+        match fs::read_to_string(file) {
+            Ok(json) => Remote::deserialize(json.as_str()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Remote::new()),
+            Err(e) => Err(IscpError::Config(format!("Cannot read {}: {}", file, e))),
         }
     }
 
-    pub fn deserialize(json: &str) -> Remote {
-        serde_json::from_str(json).unwrap()
+    pub fn deserialize(json: &str) -> Result<Remote> {
+        Ok(serde_json::from_str(json)?)
     }
 
     pub fn save(&self) -> Result<()> {
@@ -39,15 +39,81 @@ impl Remote {
     }
 
     pub fn save_to(&self, file: &str) -> Result<()> {
-        fs::write(file, self.serialize())
+        fs::write(file, self.serialize()?)?;
+        Ok(())
     }
 
-    pub fn serialize(&self) -> String {
-        serde_json::to_string_pretty(self).unwrap()
+    pub fn serialize(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
     }
 
-    pub fn discover(&mut self, duration: Duration) {
-        self.devices = discover(duration).unwrap();
+    pub fn discover(&mut self, duration: Duration) -> Result<()> {
+        self.merge(discover(duration)?);
+        Ok(())
+    }
+
+    pub fn discover_with<F: FnMut(&Device)>(
+        &mut self,
+        options: DiscoverOptions,
+        mut on_device: F,
+    ) -> Result<()> {
+        let mut devices = Vec::new();
+        for device in Discovery::start(options)? {
+            on_device(&device);
+            devices.push(device);
+        }
+        self.merge(devices);
+        Ok(())
+    }
+
+    pub fn merge(&mut self, devices: Vec<Device>) {
+        for device in devices {
+            match self
+                .devices
+                .iter_mut()
+                .find(|known| known.is_same_device(&device))
+            {
+                Some(known) => {
+                    if known.address != device.address {
+                        known.disconnect();
+                        known.address = device.address;
+                    }
+                    if !device.model.is_empty() {
+                        known.model = device.model;
+                    }
+                    if !device.area.is_empty() {
+                        known.area = device.area;
+                    }
+                    if known.mac.is_empty() {
+                        known.mac = device.mac;
+                    }
+                }
+                None => self.devices.push(device),
+            }
+        }
+    }
+
+    pub fn set_alias(&mut self, id: &str, alias: Option<&str>) -> Result<()> {
+        let index = self
+            .position(id)
+            .ok_or_else(|| IscpError::InvalidArgument(format!("Unknown device {}", id)))?;
+        if let Some(alias) = alias {
+            if alias.is_empty() {
+                return Err(IscpError::InvalidArgument(format!(
+                    "Invalid alias {}",
+                    alias
+                )));
+            }
+            if let Some(other) = self.position(alias).filter(|other| *other != index) {
+                return Err(IscpError::InvalidArgument(format!(
+                    "Alias {} is already used by {}",
+                    alias,
+                    self.devices[other].identifier()
+                )));
+            }
+        }
+        self.devices[index].alias = alias.map(String::from);
+        Ok(())
     }
 
     pub fn raw(device_address: &str, iscp_command: &str, iscp_parameter: &str) -> Result<()> {
@@ -55,7 +121,16 @@ impl Remote {
         device.raw(iscp_command, iscp_parameter)
     }
 
-    pub fn device(&self, index: usize) -> Option<&Device> {
-        self.devices.get(index)
+    pub fn device(&self, id: &str) -> Option<&Device> {
+        self.position(id).map(|index| &self.devices[index])
+    }
+
+    pub fn device_mut(&mut self, id: &str) -> Option<&mut Device> {
+        self.position(id).map(move |index| &mut self.devices[index])
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        let id = id.trim();
+        self.devices.iter().position(|device| device.matches(id))
     }
 }
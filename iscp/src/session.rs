@@ -0,0 +1,250 @@
+use super::{IscpError, IscpMessage, Link, ReceiverState, Result, Transport};
+use crate::device::ISCP_PARAM_QUERY;
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const SESSION_INBOX_LEN: usize = 64;
+const SESSION_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+type Listener = Box<dyn FnMut(&IscpMessage) -> bool + Send>;
+
+struct Waiter {
+    id: u64,
+    command: String,
+    reply: Sender<IscpMessage>,
+}
+
+pub struct Session {
+    transport: Arc<dyn Transport>,
+    closed: Arc<AtomicBool>,
+    inbox: Arc<Inbox>,
+    listeners: Arc<Mutex<Vec<Listener>>>,
+    waiters: Arc<Mutex<Vec<Waiter>>>,
+    next_waiter: AtomicU64,
+    state: Arc<Mutex<ReceiverState>>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl Session {
+    pub fn connect(address: &str) -> Result<Session> {
+        Ok(Session::open(address.parse::<Link>()?.open()?))
+    }
+
+    pub fn open(transport: Arc<dyn Transport>) -> Session {
+        let closed = Arc::new(AtomicBool::new(false));
+        let inbox = Arc::new(Inbox::default());
+        let listeners = Arc::new(Mutex::new(Vec::new()));
+        let waiters = Arc::new(Mutex::new(Vec::new()));
+        let state = Arc::new(Mutex::new(ReceiverState::default()));
+        let reader_transport = Arc::clone(&transport);
+        let reader_closed = Arc::clone(&closed);
+        let reader_inbox = Arc::clone(&inbox);
+        let reader_listeners = Arc::clone(&listeners);
+        let reader_waiters = Arc::clone(&waiters);
+        let reader_state = Arc::clone(&state);
+        let reader = thread::spawn(move || {
+            read_messages(
+                reader_transport,
+                reader_closed,
+                reader_inbox,
+                reader_listeners,
+                reader_waiters,
+                reader_state,
+            )
+        });
+
+        Session {
+            transport,
+            closed,
+            inbox,
+            listeners,
+            waiters,
+            next_waiter: AtomicU64::new(0),
+            state,
+            reader: Some(reader),
+        }
+    }
+
+    pub fn subscribe(&self) -> Receiver<IscpMessage> {
+        let (sender, receiver) = mpsc::channel();
+        self.add_listener(move |message| sender.send(message.clone()).is_ok());
+        receiver
+    }
+
+    pub fn add_listener<F>(&self, listener: F)
+    where
+        F: FnMut(&IscpMessage) -> bool + Send + 'static,
+    {
+        lock(&self.listeners).push(Box::new(listener));
+    }
+
+    pub fn send(&self, message: &IscpMessage) -> Result<()> {
+        self.transport.send(message)
+    }
+
+    pub fn query_timeout(&self, command: &str, timeout: Duration) -> Result<IscpMessage> {
+        let (reply, replies) = mpsc::channel();
+        let id = self.next_waiter.fetch_add(1, Ordering::SeqCst);
+        lock(&self.waiters).push(Waiter {
+            id,
+            command: String::from(command),
+            reply,
+        });
+        let result = self
+            .send(&IscpMessage::from(command, ISCP_PARAM_QUERY))
+            .and_then(|_| {
+                replies.recv_timeout(timeout).map_err(|e| match e {
+                    RecvTimeoutError::Timeout => IscpError::Timeout,
+                    RecvTimeoutError::Disconnected => IscpError::NotConnected,
+                })
+            });
+        lock(&self.waiters).retain(|waiter| waiter.id != id);
+        result
+    }
+
+    pub fn receive(&self) -> Result<IscpMessage> {
+        self.inbox.pop(None)
+    }
+
+    pub fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage> {
+        self.inbox.pop(Some(Instant::now() + timeout))
+    }
+
+    pub fn try_receive(&self) -> Option<IscpMessage> {
+        lock(&self.inbox.messages).messages.pop_front()
+    }
+
+    pub fn state(&self) -> ReceiverState {
+        lock(&self.state).clone()
+    }
+
+    pub fn address(&self) -> Result<String> {
+        Ok(self.transport.address())
+    }
+}
+
+#[derive(Default)]
+struct Inbox {
+    messages: Mutex<InboxMessages>,
+    available: Condvar,
+}
+
+#[derive(Default)]
+struct InboxMessages {
+    messages: VecDeque<IscpMessage>,
+    closed: bool,
+}
+
+impl Inbox {
+    fn push(&self, message: IscpMessage) {
+        let mut inbox = lock(&self.messages);
+        // A full inbox means nobody is receiving, so the stalest message makes room
+        if inbox.messages.len() == SESSION_INBOX_LEN {
+            inbox.messages.pop_front();
+        }
+        inbox.messages.push_back(message);
+        self.available.notify_all();
+    }
+
+    fn pop(&self, deadline: Option<Instant>) -> Result<IscpMessage> {
+        let mut inbox = lock(&self.messages);
+        loop {
+            if let Some(message) = inbox.messages.pop_front() {
+                return Ok(message);
+            }
+            if inbox.closed {
+                return Err(IscpError::NotConnected);
+            }
+            inbox = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining == Duration::from_secs(0) {
+                        return Err(IscpError::Timeout);
+                    }
+                    self.available
+                        .wait_timeout(inbox, remaining)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .0
+                }
+                None => self
+                    .available
+                    .wait(inbox)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            };
+        }
+    }
+
+    fn close(&self) {
+        lock(&self.messages).closed = true;
+        self.available.notify_all();
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("address", &self.transport.address())
+            .field("listeners", &lock(&self.listeners).len())
+            .finish()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.transport.close();
+        if let Some(reader) = self.reader.take() {
+            // A listener may drop the last handle from the reader thread, which cannot join itself
+            if reader.thread().id() != thread::current().id() {
+                let _ = reader.join();
+            }
+        }
+    }
+}
+
+fn read_messages(
+    transport: Arc<dyn Transport>,
+    closed: Arc<AtomicBool>,
+    inbox: Arc<Inbox>,
+    listeners: Arc<Mutex<Vec<Listener>>>,
+    waiters: Arc<Mutex<Vec<Waiter>>>,
+    state: Arc<Mutex<ReceiverState>>,
+) {
+    while !closed.load(Ordering::SeqCst) {
+        match transport.receive_timeout(SESSION_POLL_INTERVAL) {
+            Ok(message) => {
+                lock(&state).update(&message);
+                inbox.push(message.clone());
+                lock(&waiters).retain(|waiter| {
+                    if waiter.command != message.command {
+                        return true;
+                    }
+                    let _ = waiter.reply.send(message.clone());
+                    false
+                });
+                // Listeners run unlocked so they can register further listeners
+                let mut notified = mem::take(&mut *lock(&listeners));
+                notified.retain_mut(|listener| listener(&message));
+                let mut listeners = lock(&listeners);
+                notified.append(&mut listeners);
+                *listeners = notified;
+            }
+            Err(IscpError::Timeout) => {}
+            Err(_) => break,
+        }
+    }
+    inbox.close();
+    lock(&waiters).clear();
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
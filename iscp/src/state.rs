@@ -0,0 +1,17 @@
+use super::{ArtAssembler, IscpMessage, NowPlaying, RdsInfo};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReceiverState {
+    pub rds: RdsInfo,
+    pub now_playing: NowPlaying,
+    pub art: ArtAssembler,
+}
+
+impl ReceiverState {
+    pub fn update(&mut self, message: &IscpMessage) -> bool {
+        let rds = self.rds.update(message);
+        let now_playing = self.now_playing.update(message);
+        let art = self.art.update(message);
+        rds || now_playing || art
+    }
+}
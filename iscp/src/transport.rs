@@ -0,0 +1,400 @@
+use super::{Framing, IscpDecoder, IscpError, IscpMessage, Result};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::prelude::*;
+#[cfg(feature = "serial")]
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpStream};
+use std::str::FromStr;
+#[cfg(feature = "serial")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+pub const SERIAL_DEFAULT_BAUD_RATE: u32 = 9600;
+
+#[cfg(feature = "serial")]
+const SERIAL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+static SERIAL_LINK_PREFIX: &str = "serial:";
+
+pub trait Transport: Send + Sync {
+    fn send(&self, message: &IscpMessage) -> Result<()>;
+
+    fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage>;
+
+    fn close(&self);
+
+    fn address(&self) -> String;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    Tcp(String),
+    Serial { path: String, baud_rate: u32 },
+}
+
+impl Link {
+    pub fn open(&self) -> Result<Arc<dyn Transport>> {
+        match self {
+            Link::Tcp(address) => Ok(Arc::new(TcpTransport::connect(address)?)),
+            #[cfg(feature = "serial")]
+            Link::Serial { path, baud_rate } => {
+                Ok(Arc::new(SerialTransport::open(path, *baud_rate)?))
+            }
+            #[cfg(not(feature = "serial"))]
+            Link::Serial { .. } => Err(IscpError::Unsupported(String::from(
+                "Serial links require the serial feature",
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Link::Tcp(address) => f.write_str(address),
+            Link::Serial { path, baud_rate } => {
+                write!(f, "{}{}@{}", SERIAL_LINK_PREFIX, path, baud_rate)
+            }
+        }
+    }
+}
+
+impl FromStr for Link {
+    type Err = IscpError;
+
+    fn from_str(s: &str) -> std::result::Result<Link, IscpError> {
+        let serial = match s.strip_prefix(SERIAL_LINK_PREFIX) {
+            Some(serial) => serial,
+            None => return Ok(Link::Tcp(String::from(s))),
+        };
+        let (path, baud_rate) = match serial.rsplit_once('@') {
+            Some((path, baud_rate)) => (
+                path,
+                baud_rate.parse().map_err(|_| {
+                    IscpError::InvalidArgument(format!("Invalid baud rate {}", baud_rate))
+                })?,
+            ),
+            None => (serial, SERIAL_DEFAULT_BAUD_RATE),
+        };
+        if path.is_empty() {
+            return Err(IscpError::InvalidArgument(format!(
+                "Missing serial port in {}",
+                s
+            )));
+        }
+        Ok(Link::Serial {
+            path: String::from(path),
+            baud_rate,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpTransport {
+    stream: TcpStream,
+    decoder: Mutex<IscpDecoder>,
+}
+
+impl TcpTransport {
+    pub fn connect(address: &str) -> Result<TcpTransport> {
+        let stream = TcpStream::connect(address).map_err(IscpError::Connect)?;
+        Ok(TcpTransport::from(stream))
+    }
+
+    pub fn from(stream: TcpStream) -> TcpTransport {
+        TcpTransport {
+            stream,
+            decoder: Mutex::new(IscpDecoder::with_framing(Framing::Eiscp)),
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&self, message: &IscpMessage) -> Result<()> {
+        (&self.stream).write_all(message.encode(Framing::Eiscp)?.as_ref())?;
+        Ok(())
+    }
+
+    fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage> {
+        let mut decoder = lock(&self.decoder);
+        receive_from(&mut decoder, timeout, |buf, remaining| {
+            self.stream.set_read_timeout(Some(remaining))?;
+            (&self.stream).read(buf)
+        })
+    }
+
+    fn close(&self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    fn address(&self) -> String {
+        self.stream
+            .peer_addr()
+            .map(|addr| addr.to_string())
+            .unwrap_or_default()
+    }
+}
+
+type Responder = Box<dyn Fn(&IscpMessage) -> Vec<IscpMessage> + Send + Sync>;
+
+#[derive(Default)]
+pub struct MemoryTransport {
+    sent: Mutex<Vec<IscpMessage>>,
+    inbox: Mutex<MemoryInbox>,
+    available: Condvar,
+    responder: Option<Responder>,
+}
+
+#[derive(Default)]
+struct MemoryInbox {
+    messages: VecDeque<IscpMessage>,
+    closed: bool,
+}
+
+impl MemoryTransport {
+    pub fn new() -> MemoryTransport {
+        MemoryTransport::default()
+    }
+
+    pub fn with_responder<F>(responder: F) -> MemoryTransport
+    where
+        F: Fn(&IscpMessage) -> Vec<IscpMessage> + Send + Sync + 'static,
+    {
+        MemoryTransport {
+            responder: Some(Box::new(responder)),
+            ..MemoryTransport::default()
+        }
+    }
+
+    pub fn push(&self, message: IscpMessage) {
+        lock(&self.inbox).messages.push_back(message);
+        self.available.notify_all();
+    }
+
+    pub fn sent(&self) -> Vec<IscpMessage> {
+        lock(&self.sent).clone()
+    }
+
+    pub fn take_sent(&self) -> Vec<IscpMessage> {
+        lock(&self.sent).drain(..).collect()
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, message: &IscpMessage) -> Result<()> {
+        message.encode(Framing::Eiscp)?;
+        if lock(&self.inbox).closed {
+            return Err(IscpError::NotConnected);
+        }
+        lock(&self.sent).push(message.clone());
+        if let Some(responder) = &self.responder {
+            for reply in responder(message) {
+                self.push(reply);
+            }
+        }
+        Ok(())
+    }
+
+    fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage> {
+        let deadline = Instant::now() + timeout;
+        let mut inbox = lock(&self.inbox);
+        loop {
+            if let Some(message) = inbox.messages.pop_front() {
+                return Ok(message);
+            }
+            if inbox.closed {
+                return Err(IscpError::NotConnected);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining == Duration::from_secs(0) {
+                return Err(IscpError::Timeout);
+            }
+            inbox = self
+                .available
+                .wait_timeout(inbox, remaining)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+    }
+
+    fn close(&self) {
+        lock(&self.inbox).closed = true;
+        self.available.notify_all();
+    }
+
+    fn address(&self) -> String {
+        String::from("memory")
+    }
+}
+
+impl fmt::Debug for MemoryTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryTransport")
+            .field("sent", &lock(&self.sent).len())
+            .field("pending", &lock(&self.inbox).messages.len())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct RecordingTransport<T: Transport> {
+    inner: T,
+    sent: Mutex<Vec<IscpMessage>>,
+    received: Mutex<Vec<IscpMessage>>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn from(inner: T) -> RecordingTransport<T> {
+        RecordingTransport {
+            inner,
+            sent: Mutex::new(Vec::new()),
+            received: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn sent(&self) -> Vec<IscpMessage> {
+        lock(&self.sent).clone()
+    }
+
+    pub fn received(&self) -> Vec<IscpMessage> {
+        lock(&self.received).clone()
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn send(&self, message: &IscpMessage) -> Result<()> {
+        self.inner.send(message)?;
+        lock(&self.sent).push(message.clone());
+        Ok(())
+    }
+
+    fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage> {
+        let message = self.inner.receive_timeout(timeout)?;
+        lock(&self.received).push(message.clone());
+        Ok(message)
+    }
+
+    fn close(&self) {
+        self.inner.close();
+    }
+
+    fn address(&self) -> String {
+        self.inner.address()
+    }
+}
+
+#[cfg(feature = "serial")]
+pub struct SerialTransport {
+    path: String,
+    reader: Mutex<(Box<dyn serialport::SerialPort>, IscpDecoder)>,
+    writer: Mutex<Option<Box<dyn serialport::SerialPort>>>,
+    closed: AtomicBool,
+}
+
+#[cfg(feature = "serial")]
+impl SerialTransport {
+    pub fn open(path: &str, baud_rate: u32) -> Result<SerialTransport> {
+        let writer = serialport::new(path, baud_rate)
+            .open()
+            .map_err(|e| IscpError::Connect(e.into()))?;
+        let reader = writer
+            .try_clone()
+            .map_err(|e| IscpError::Connect(e.into()))?;
+        Ok(SerialTransport {
+            path: String::from(path),
+            reader: Mutex::new((reader, IscpDecoder::with_framing(Framing::Iscp))),
+            writer: Mutex::new(Some(writer)),
+            closed: AtomicBool::new(false),
+        })
+    }
+}
+
+#[cfg(feature = "serial")]
+impl Transport for SerialTransport {
+    fn send(&self, message: &IscpMessage) -> Result<()> {
+        let mut writer = lock(&self.writer);
+        let writer = writer.as_mut().ok_or(IscpError::NotConnected)?;
+        writer.write_all(message.encode(Framing::Iscp)?.as_ref())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn receive_timeout(&self, timeout: Duration) -> Result<IscpMessage> {
+        let mut reader = lock(&self.reader);
+        let (port, decoder) = &mut *reader;
+        receive_from(decoder, timeout, |buf, remaining| {
+            // Serial ports cannot be shut down from another thread, so reads poll for close
+            let deadline = Instant::now() + remaining;
+            loop {
+                if self.closed.load(Ordering::SeqCst) {
+                    return Ok(0);
+                }
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                port.set_timeout(remaining.min(SERIAL_POLL_INTERVAL))?;
+                match port.read(buf) {
+                    Err(e) if e.kind() == ErrorKind::TimedOut && Instant::now() < deadline => {}
+                    result => return result,
+                }
+            }
+        })
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        lock(&self.writer).take();
+    }
+
+    fn address(&self) -> String {
+        let baud_rate = lock(&self.writer)
+            .as_ref()
+            .and_then(|writer| writer.baud_rate().ok())
+            .unwrap_or(SERIAL_DEFAULT_BAUD_RATE);
+        Link::Serial {
+            path: self.path.clone(),
+            baud_rate,
+        }
+        .to_string()
+    }
+}
+
+#[cfg(feature = "serial")]
+impl fmt::Debug for SerialTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerialTransport")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+fn receive_from<F>(decoder: &mut IscpDecoder, timeout: Duration, mut read: F) -> Result<IscpMessage>
+where
+    F: FnMut(&mut [u8], Duration) -> std::io::Result<usize>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0; 1024];
+    loop {
+        if let Some(message) = decoder.decode() {
+            return Ok(message);
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::from_secs(0) {
+            return Err(IscpError::Timeout);
+        }
+        match read(&mut buf, remaining)? {
+            0 => return Err(IscpError::NotConnected),
+            n => decoder.feed(&buf[..n]),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "async")]
+
+use bytes::BytesMut;
+use futures_util::StreamExt;
+use iscp::{
+    AsyncDevice, AsyncSession, Device, IscpCodec, IscpError, IscpMessage, MockReceiver, PowerState,
+    Zone,
+};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_util::codec::{Decoder, Encoder};
+
+async fn receiver(replies: &'static [(&'static str, &'static str)]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0; 1024];
+                while let Ok(n) = stream.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                    for (command, parameter) in replies {
+                        let reply = IscpMessage::from(command, parameter).bytes().unwrap();
+                        let _ = stream.write_all(reply.as_ref()).await;
+                    }
+                }
+            });
+        }
+    });
+    address
+}
+
+#[test]
+fn encodes_and_decodes_messages() {
+    let mut codec = IscpCodec;
+    let mut buf = BytesMut::new();
+    codec
+        .encode(IscpMessage::from("PWR", "01"), &mut buf)
+        .unwrap();
+    codec
+        .encode(IscpMessage::from("MVL", "2A"), &mut buf)
+        .unwrap();
+    let packets = buf.split().freeze();
+
+    buf.extend_from_slice(&packets[..10]);
+    assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    buf.extend_from_slice(&packets[10..]);
+    assert_eq!(
+        codec.decode(&mut buf).unwrap(),
+        Some(IscpMessage::from("PWR", "01"))
+    );
+    assert_eq!(
+        codec.decode(&mut buf).unwrap(),
+        Some(IscpMessage::from("MVL", "2A"))
+    );
+    assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+    buf.extend_from_slice(b"ISCP\x00\x00");
+    assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+    assert!(buf.is_empty());
+    match codec.encode(IscpMessage::from("PW", "01"), &mut buf) {
+        Err(IscpError::MalformedPacket(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn queries_over_tcp() {
+    let address = receiver(&[("NTI", "Song"), ("MVL", "2A")]).await;
+    let mut session = AsyncSession::connect(&address).await.unwrap();
+
+    assert_eq!(
+        session.query("MVL").await.unwrap(),
+        IscpMessage::from("MVL", "2A")
+    );
+    assert_eq!(
+        session.receive().await.unwrap(),
+        IscpMessage::from("NTI", "Song")
+    );
+    session.raw("NTC", "PLAY").await.unwrap();
+    assert_eq!(
+        session.next().await.unwrap().unwrap(),
+        IscpMessage::from("NTI", "Song")
+    );
+}
+
+#[tokio::test]
+async fn reports_unavailable_and_unanswered_queries() {
+    let address = receiver(&[("SLI", "N/A")]).await;
+    let mut session = Device::from_address(&address)
+        .connect_async()
+        .await
+        .unwrap();
+
+    match session.query("SLI").await {
+        Err(IscpError::NotAvailable(command)) => assert_eq!(command, "SLI"),
+        result => panic!("Unexpected result {:?}", result),
+    }
+    match session
+        .query_timeout("PWR", Duration::from_millis(100))
+        .await
+    {
+        Err(IscpError::Timeout) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn runs_commands_from_async_code() {
+    let address = receiver(&[("PWR", "01")]).await;
+    let device = AsyncDevice::from(Device::from_address(&address));
+
+    device.power_on().await.unwrap();
+    assert_eq!(device.power_state().await.unwrap(), PowerState::On);
+    assert!(device.device().is_connected());
+}
+
+#[tokio::test]
+async fn queries_mock_receiver() {
+    let device = Device::from(
+        String::from("127.0.0.1:0"),
+        String::from("TX-SIM"),
+        String::from("XX"),
+        String::from("0009B0000001"),
+    );
+    let receiver = MockReceiver::start(&device, None).unwrap();
+    let mut session = receiver.device().connect_async().await.unwrap();
+
+    session.raw("MVL", "2A").await.unwrap();
+    session.raw("CTL", "-3").await.unwrap();
+    assert_eq!(
+        session.query("MVL").await.unwrap(),
+        IscpMessage::from("MVL", "2A")
+    );
+    assert_eq!(
+        session.query("CTL").await.unwrap(),
+        IscpMessage::from("CTL", "-3")
+    );
+    assert_eq!(receiver.volume(Zone::Main), Some(0x2A));
+}
@@ -0,0 +1,284 @@
+use iscp::{
+    Band, Device, DspFeature, DspLevel, Frequency, InputSource, IscpError, IscpMessage,
+    LevelChannel, ListeningMode, MemoryTransport, NetKey, PowerState, RecordingTransport, Speaker,
+    Tone, ToneSetting, Zone, ZoneCommands,
+};
+use std::sync::Arc;
+
+fn device() -> (Device, Arc<MemoryTransport>) {
+    let transport = Arc::new(MemoryTransport::new());
+    (Device::from_transport(transport.clone()), transport)
+}
+
+fn device_with<F>(responder: F) -> (Device, Arc<MemoryTransport>)
+where
+    F: Fn(&IscpMessage) -> Vec<IscpMessage> + Send + Sync + 'static,
+{
+    let transport = Arc::new(MemoryTransport::with_responder(responder));
+    (Device::from_transport(transport.clone()), transport)
+}
+
+fn answering(
+    answers: &'static [(&'static str, &'static str)],
+) -> impl Fn(&IscpMessage) -> Vec<IscpMessage> + Send + Sync + 'static {
+    move |message| {
+        let parameter = answers
+            .iter()
+            .find(|(command, _)| message.parameter == "QSTN" && message.command == *command)
+            .map_or(message.parameter.as_str(), |(_, parameter)| parameter);
+        vec![IscpMessage::from(&message.command, parameter)]
+    }
+}
+
+#[test]
+fn emits_main_zone_packets() {
+    let (device, transport) = device();
+    let commands = device.commands();
+    let main = commands.main();
+    main.power_on().unwrap();
+    main.mute().unwrap();
+    main.set_volume(0x2A).unwrap();
+    main.select_input(InputSource::from_code(0x10).unwrap())
+        .unwrap();
+    main.set_listening_mode(ListeningMode::from_code(0x00).unwrap())
+        .unwrap();
+    main.set_tone_front_bass(-4).unwrap();
+    main.set_tone_front_treble(0).unwrap();
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("PWR", "01"),
+            IscpMessage::from("AMT", "01"),
+            IscpMessage::from("MVL", "2A"),
+            IscpMessage::from("SLI", "10"),
+            IscpMessage::from("LMD", "00"),
+            IscpMessage::from("TFR", "B-4"),
+            IscpMessage::from("TFR", "T00"),
+        ]
+    );
+}
+
+#[test]
+fn clamps_volume_to_maximum() {
+    let (device, transport) = device();
+    device.commands().main().set_volume(200).unwrap();
+    assert_eq!(transport.sent(), vec![IscpMessage::from("MVL", "64")]);
+}
+
+#[test]
+fn emits_zone_packets() {
+    let (device, transport) = device();
+    device.commands().zone(Zone::Zone2).power_off().unwrap();
+    device.commands().zone(Zone::Zone3).set_volume_up().unwrap();
+    device.commands().zone4().unmute().unwrap();
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("ZPW", "00"),
+            IscpMessage::from("VL3", "UP"),
+            IscpMessage::from("MT4", "00"),
+        ]
+    );
+}
+
+#[test]
+fn queries_through_transport() {
+    let (device, transport) =
+        device_with(answering(&[("PWR", "01"), ("MVL", "2A"), ("SLI", "N/A")]));
+    let commands = device.commands();
+    let main = commands.main();
+
+    assert_eq!(main.power_state().unwrap(), PowerState::On);
+    assert_eq!(main.volume().unwrap(), 0x2A);
+    match main.input() {
+        Err(IscpError::NotAvailable(command)) => assert_eq!(command, "SLI"),
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("PWR", "QSTN"),
+            IscpMessage::from("MVL", "QSTN"),
+            IscpMessage::from("SLI", "QSTN"),
+        ]
+    );
+}
+
+#[test]
+fn records_sent_and_received_packets() {
+    let transport = Arc::new(RecordingTransport::from(MemoryTransport::with_responder(
+        answering(&[("PWR", "01")]),
+    )));
+    let device = Device::from_transport(transport.clone());
+    assert_eq!(
+        device.commands().main().power_state().unwrap(),
+        PowerState::On
+    );
+
+    assert_eq!(transport.sent(), vec![IscpMessage::from("PWR", "QSTN")]);
+    assert_eq!(transport.received(), vec![IscpMessage::from("PWR", "01")]);
+}
+
+#[test]
+fn emits_speaker_tone_packets() {
+    let (device, transport) = device_with(answering(&[("TSW", "B-6")]));
+    let commands = device.commands();
+    let main = commands.main();
+    main.set_tone(Speaker::Center, ToneSetting::Treble, 10)
+        .unwrap();
+    main.set_tone_up(Speaker::Zone2, ToneSetting::Bass).unwrap();
+    assert_eq!(
+        main.tone(Speaker::Subwoofer).unwrap(),
+        Tone {
+            bass: -6,
+            treble: 0
+        }
+    );
+    match main.set_tone(Speaker::Subwoofer, ToneSetting::Treble, 2) {
+        Err(IscpError::Unsupported(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    match main.set_tone(Speaker::Center, ToneSetting::Bass, 11) {
+        Err(IscpError::InvalidArgument(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    match commands.zone2().set_tone_treble(-11) {
+        Err(IscpError::InvalidArgument(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("TCT", "T+A"),
+            IscpMessage::from("ZTN", "BUP"),
+            IscpMessage::from("TSW", "QSTN"),
+        ]
+    );
+}
+
+#[test]
+fn emits_channel_level_packets() {
+    let (device, transport) = device_with(answering(&[("SWL", "-F")]));
+    let commands = device.commands();
+    let main = commands.main();
+    main.set_center_level(3).unwrap();
+    main.set_center_level(0).unwrap();
+    main.set_channel_level(LevelChannel::Subwoofer2, -15)
+        .unwrap();
+    match main.set_channel_level(LevelChannel::Subwoofer2, -20) {
+        Err(IscpError::InvalidArgument(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert!(main.set_channel_level(LevelChannel::Center, 13).is_err());
+    main.set_channel_level_down(LevelChannel::Center).unwrap();
+    assert_eq!(main.subwoofer_level().unwrap(), -15);
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("CTL", "+3"),
+            IscpMessage::from("CTL", "00"),
+            IscpMessage::from("SW2", "-F"),
+            IscpMessage::from("CTL", "DOWN"),
+            IscpMessage::from("SWL", "QSTN"),
+        ]
+    );
+}
+
+#[test]
+fn emits_dsp_packets() {
+    let (device, transport) = device_with(answering(&[("LTN", "02")]));
+    let commands = device.commands();
+    let main = commands.main();
+    main.set_dsp(DspFeature::DynamicVolume, DspLevel::Heavy)
+        .unwrap();
+    main.cycle_dsp(DspFeature::Direct).unwrap();
+    assert_eq!(main.dsp(DspFeature::LateNight).unwrap(), DspLevel::High);
+    match main.set_dsp(DspFeature::DynamicEq, DspLevel::High) {
+        Err(IscpError::Unsupported(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("ADV", "03"),
+            IscpMessage::from("DIR", "TG"),
+            IscpMessage::from("LTN", "QSTN"),
+        ]
+    );
+}
+
+#[test]
+fn emits_tuner_packets() {
+    let (device, transport) = device_with(answering(&[("TUN", "10150"), ("PRS", "1E")]));
+    let commands = device.commands();
+    let tuner = commands.tuner();
+    tuner.tune(Frequency::fm(101.5).unwrap()).unwrap();
+    tuner.tune(Frequency::am(1230).unwrap()).unwrap();
+    tuner.recall_preset(3).unwrap();
+    tuner.store_preset(40).unwrap();
+    assert!(tuner.recall_preset(41).is_err());
+    assert!(Frequency::fm(120.0).is_err());
+    assert_eq!(
+        tuner.frequency().unwrap(),
+        Frequency {
+            band: Band::Fm,
+            khz: 101_500
+        }
+    );
+    assert_eq!(tuner.preset().unwrap(), 30);
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("SLI", "24"),
+            IscpMessage::from("TUN", "10150"),
+            IscpMessage::from("SLI", "25"),
+            IscpMessage::from("TUN", "01230"),
+            IscpMessage::from("PRS", "03"),
+            IscpMessage::from("PRM", "28"),
+            IscpMessage::from("TUN", "QSTN"),
+            IscpMessage::from("PRS", "QSTN"),
+        ]
+    );
+}
+
+#[test]
+fn emits_net_key_packets() {
+    let (device, transport) = device();
+    let commands = device.commands();
+    let net = commands.net();
+    net.play().unwrap();
+    net.next_track().unwrap();
+    net.previous_track().unwrap();
+    net.press("Return".parse().unwrap()).unwrap();
+    net.press(NetKey::number(7).unwrap()).unwrap();
+    assert_eq!(NetKey::number(10), None);
+
+    assert_eq!(
+        transport.sent(),
+        vec![
+            IscpMessage::from("NTC", "PLAY"),
+            IscpMessage::from("NTC", "TRUP"),
+            IscpMessage::from("NTC", "TRDN"),
+            IscpMessage::from("NTC", "RETURN"),
+            IscpMessage::from("NTC", "7"),
+        ]
+    );
+}
+
+#[test]
+fn clones_share_connection() {
+    let (device, transport) = device_with(answering(&[("PWR", "01")]));
+    let clone = device.clone();
+    assert!(clone.is_connected());
+    assert_eq!(clone.query("PWR").unwrap(), IscpMessage::from("PWR", "01"));
+
+    device.disconnect();
+    assert!(clone.is_connected());
+    assert_eq!(transport.sent(), vec![IscpMessage::from("PWR", "QSTN")]);
+}
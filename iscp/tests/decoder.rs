@@ -0,0 +1,120 @@
+use iscp::{Framing, IscpDecoder, IscpMessage};
+
+fn packet(command: &str, parameter: &str) -> Vec<u8> {
+    IscpMessage::from(command, parameter)
+        .bytes()
+        .unwrap()
+        .to_vec()
+}
+
+#[test]
+fn decodes_single_packet() {
+    let mut decoder = IscpDecoder::new();
+    decoder.feed(&packet("PWR", "01"));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("PWR", "01")));
+    assert_eq!(decoder.decode(), None);
+    assert_eq!(decoder.len_buffered(), 0);
+}
+
+#[test]
+fn decodes_concatenated_packets() {
+    let mut data = packet("PWR", "01");
+    data.extend(packet("MVL", "2A"));
+    data.extend(packet("AMT", "00"));
+
+    let mut decoder = IscpDecoder::new();
+    decoder.feed(&data);
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("PWR", "01")));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("MVL", "2A")));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("AMT", "00")));
+    assert_eq!(decoder.decode(), None);
+}
+
+#[test]
+fn reassembles_fragmented_packet() {
+    let data = packet("SLI", "23");
+    let mut decoder = IscpDecoder::new();
+    for byte in &data[..data.len() - 1] {
+        decoder.feed(&[*byte]);
+        assert_eq!(decoder.decode(), None);
+    }
+    decoder.feed(&data[data.len() - 1..]);
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("SLI", "23")));
+}
+
+#[test]
+fn splits_packets_across_reads() {
+    let mut data = packet("PWR", "01");
+    data.extend(packet("MVL", "2A"));
+    let (first, second) = data.split_at(20);
+
+    let mut decoder = IscpDecoder::new();
+    decoder.feed(first);
+    assert_eq!(decoder.decode(), None);
+    decoder.feed(second);
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("PWR", "01")));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("MVL", "2A")));
+}
+
+#[test]
+fn resynchronises_after_garbage() {
+    let mut data = b"garbage IS".to_vec();
+    data.extend(packet("PWR", "00"));
+    data.extend(b"\x00\x01ISC");
+    data.extend(packet("AMT", "01"));
+
+    let mut decoder = IscpDecoder::new();
+    decoder.feed(&data);
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("PWR", "00")));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("AMT", "01")));
+    assert_eq!(decoder.decode(), None);
+}
+
+#[test]
+fn skips_packet_with_invalid_header_length() {
+    let mut data = packet("PWR", "00");
+    data[4..8].copy_from_slice(&4u32.to_be_bytes());
+    data.extend(packet("AMT", "01"));
+    let mut oversized = packet("MVL", "20");
+    oversized[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+    data.extend(oversized);
+    data.extend(packet("PWR", "01"));
+
+    let mut decoder = IscpDecoder::new();
+    decoder.feed(&data);
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("AMT", "01")));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("PWR", "01")));
+    assert_eq!(decoder.decode(), None);
+}
+
+#[test]
+fn strips_message_terminators() {
+    let mut data = packet("NTI", "Song");
+    let len = data.len();
+    data.truncate(len - 1);
+    data.extend(b"\x1a\r\n");
+    data[8..12].copy_from_slice(&((len - 16 + 2) as u32).to_be_bytes());
+
+    let mut decoder = IscpDecoder::new();
+    decoder.feed(&data);
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("NTI", "Song")));
+}
+
+#[test]
+fn encodes_serial_framing() {
+    let data = IscpMessage::from("PWR", "01")
+        .encode(Framing::Iscp)
+        .unwrap();
+    assert_eq!(data.as_ref(), b"!1PWR01\r");
+}
+
+#[test]
+fn decodes_serial_framing() {
+    let mut decoder = IscpDecoder::with_framing(Framing::Iscp);
+    decoder.feed(b"\x1a\r\n!1PWR0");
+    assert_eq!(decoder.decode(), None);
+    decoder.feed(b"1\x1a\r\n!1MVL2A\r");
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("PWR", "01")));
+    assert_eq!(decoder.decode(), Some(IscpMessage::from("MVL", "2A")));
+    assert_eq!(decoder.decode(), None);
+}
@@ -0,0 +1,305 @@
+use iscp::{DiscoverOptions, DiscoverTarget, Discovery, DiscoveryReply, IscpError, IscpMessage};
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Discovery always binds the well-known port, so only one test may run it at a time
+static DISCOVERY_PORT: Mutex<()> = Mutex::new(());
+
+struct FakeReceiver {
+    address: SocketAddr,
+    queries: Arc<AtomicUsize>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FakeReceiver {
+    fn start(identity: &str, replies: usize) -> FakeReceiver {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let address = socket.local_addr().unwrap();
+        let queries = Arc::new(AtomicUsize::new(0));
+        let running = Arc::new(AtomicBool::new(true));
+        let reply = ecn(identity);
+        let thread_queries = Arc::clone(&queries);
+        let thread_running = Arc::clone(&running);
+        let thread = thread::spawn(move || {
+            let mut buf = [0; 1024];
+            while thread_running.load(Ordering::SeqCst) {
+                if let Ok((_, source)) = socket.recv_from(&mut buf) {
+                    thread_queries.fetch_add(1, Ordering::SeqCst);
+                    for _ in 0..replies {
+                        let _ = socket.send_to(&reply, source);
+                    }
+                }
+            }
+        });
+        FakeReceiver {
+            address,
+            queries,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    fn options(&self) -> DiscoverOptions {
+        DiscoverOptions {
+            timeout: Duration::from_secs(2),
+            retransmissions: 0,
+            targets: vec![DiscoverTarget::Host(self.address.to_string())],
+            ..DiscoverOptions::default()
+        }
+    }
+
+    fn queries(&self) -> usize {
+        self.queries.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for FakeReceiver {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn ecn(identity: &str) -> Vec<u8> {
+    IscpMessage::from("ECN", identity).bytes().unwrap().to_vec()
+}
+
+fn source() -> SocketAddr {
+    "192.168.1.20:60128".parse().unwrap()
+}
+
+fn discovery_port() -> MutexGuard<'static, ()> {
+    DISCOVERY_PORT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[test]
+fn parses_discovery_reply() {
+    let reply =
+        DiscoveryReply::parse(&ecn("TX-NR676/60128/DX/0009B0123456\x1a\r\n"), source()).unwrap();
+    assert_eq!(
+        reply,
+        DiscoveryReply {
+            model: String::from("TX-NR676"),
+            port: 60128,
+            area: String::from("DX"),
+            mac: String::from("0009B0123456"),
+            source: source(),
+        }
+    );
+    assert_eq!(reply.address(), source());
+    assert_eq!(reply.device().address, "192.168.1.20:60128");
+}
+
+#[test]
+fn rejects_truncated_discovery_reply() {
+    for identity in &[
+        "TX-NR676/60128/DX",
+        "/60128/DX/0009B0123456",
+        "TX-NR676/port/DX/0009B0123456",
+    ] {
+        match DiscoveryReply::parse(&ecn(identity), source()) {
+            Err(IscpError::MalformedPacket(_)) => {}
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+    let packet = ecn("TX-NR676/60128/DX/0009B0123456");
+    assert!(DiscoveryReply::parse(&packet[..packet.len() - 10], source()).is_err());
+}
+
+#[test]
+fn rejects_discovery_reply_with_wrong_command() {
+    let packet = IscpMessage::from("PWR", "01").bytes().unwrap();
+    match DiscoveryReply::parse(packet.as_ref(), source()) {
+        Err(IscpError::MalformedPacket(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn ignores_duplicate_discovery_replies() {
+    let _port = discovery_port();
+    let receiver = FakeReceiver::start("TX-SIM/60128/XX/0009B0000001", 3);
+    let options = DiscoverOptions {
+        quiet_period: Some(Duration::from_millis(300)),
+        ..receiver.options()
+    };
+    let devices: Vec<_> = Discovery::start(options).unwrap().collect();
+
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].model, "TX-SIM");
+    assert_eq!(devices[0].mac, "0009B0000001");
+    assert_eq!(receiver.queries(), 1);
+}
+
+#[test]
+fn retransmits_until_timeout() {
+    let _port = discovery_port();
+    let receiver = FakeReceiver::start("TX-SIM/60128/XX/0009B0000001", 0);
+    let options = DiscoverOptions {
+        timeout: Duration::from_millis(600),
+        retransmissions: 2,
+        retransmit_interval: Duration::from_millis(100),
+        ..receiver.options()
+    };
+    let started = Instant::now();
+    let devices: Vec<_> = Discovery::start(options).unwrap().collect();
+
+    assert!(devices.is_empty());
+    assert!(started.elapsed() >= Duration::from_millis(600));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(receiver.queries(), 3);
+}
+
+#[test]
+fn stops_after_limit() {
+    let _port = discovery_port();
+    let first = FakeReceiver::start("TX-SIM/60128/XX/0009B0000001", 1);
+    let second = FakeReceiver::start("TX-SIM/60128/XX/0009B0000002", 1);
+    let options = DiscoverOptions {
+        timeout: Duration::from_secs(5),
+        limit: Some(1),
+        targets: vec![
+            DiscoverTarget::Host(first.address.to_string()),
+            DiscoverTarget::Host(second.address.to_string()),
+        ],
+        ..first.options()
+    };
+    let started = Instant::now();
+    let mut discovery = Discovery::start(options).unwrap();
+
+    assert!(discovery.next().is_some());
+    assert!(discovery.next().is_none());
+    assert_eq!(discovery.replies().len(), 1);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn stops_after_quiet_period() {
+    let _port = discovery_port();
+    let receiver = FakeReceiver::start("TX-SIM/60128/XX/0009B0000001", 1);
+    let options = DiscoverOptions {
+        timeout: Duration::from_secs(5),
+        quiet_period: Some(Duration::from_millis(200)),
+        ..receiver.options()
+    };
+    let started = Instant::now();
+    let devices: Vec<_> = Discovery::start(options).unwrap().collect();
+
+    assert_eq!(devices.len(), 1);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn discovers_with_options_from_async_code() {
+    let _port = discovery_port();
+    let receiver = FakeReceiver::start("TX-SIM/60128/XX/0009B0000001", 2);
+    let options = DiscoverOptions {
+        timeout: Duration::from_secs(5),
+        limit: Some(1),
+        retransmissions: 2,
+        retransmit_interval: Duration::from_secs(1),
+        ..receiver.options()
+    };
+    let started = Instant::now();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let devices = runtime.block_on(iscp::discover_async(options)).unwrap();
+
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].mac, "0009B0000001");
+    assert_eq!(receiver.queries(), 1);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn parses_discovery_targets() {
+    assert_eq!(
+        "broadcast".parse::<DiscoverTarget>().unwrap(),
+        DiscoverTarget::Broadcast
+    );
+    assert_eq!(
+        " 192.168.1.0/24 ".parse::<DiscoverTarget>().unwrap(),
+        DiscoverTarget::Network(Ipv4Addr::new(192, 168, 1, 0), 24)
+    );
+    assert_eq!(
+        "receiver.local".parse::<DiscoverTarget>().unwrap(),
+        DiscoverTarget::Host(String::from("receiver.local"))
+    );
+    assert_eq!(
+        "10.1.0.0/16".parse::<DiscoverTarget>().unwrap().to_string(),
+        "10.1.0.0/16"
+    );
+}
+
+#[test]
+fn rejects_invalid_discovery_targets() {
+    for target in &[
+        "",
+        "10.0.0.0/15",
+        "10.0.0.0/0",
+        "10.0.0.0/33",
+        "10.0.0.0/x",
+        "host/24",
+    ] {
+        match target.parse::<DiscoverTarget>() {
+            Err(IscpError::InvalidArgument(_)) => {}
+            result => panic!("Unexpected result for {:?}: {:?}", target, result),
+        }
+    }
+}
+
+#[test]
+fn expands_discovery_target_addresses() {
+    let addresses = "192.168.1.77/24"
+        .parse::<DiscoverTarget>()
+        .unwrap()
+        .addresses()
+        .unwrap();
+    assert_eq!(addresses.len(), 254);
+    assert_eq!(addresses[0], "192.168.1.1:60128".parse().unwrap());
+    assert_eq!(addresses[253], "192.168.1.254:60128".parse().unwrap());
+
+    let point_to_point = DiscoverTarget::Network(Ipv4Addr::new(10, 0, 0, 1), 31);
+    assert_eq!(point_to_point.addresses().unwrap().len(), 2);
+    let single = DiscoverTarget::Network(Ipv4Addr::new(10, 0, 0, 1), 32);
+    assert_eq!(
+        single.addresses().unwrap(),
+        vec!["10.0.0.1:60128".parse::<SocketAddr>().unwrap()]
+    );
+    assert_eq!(
+        DiscoverTarget::Network(Ipv4Addr::new(10, 0, 0, 0), 16)
+            .addresses()
+            .unwrap()
+            .len(),
+        65534
+    );
+}
+
+#[test]
+fn resolves_host_discovery_targets() {
+    assert_eq!(
+        DiscoverTarget::Host(String::from("127.0.0.1"))
+            .addresses()
+            .unwrap(),
+        vec!["127.0.0.1:60128".parse::<SocketAddr>().unwrap()]
+    );
+    assert_eq!(
+        DiscoverTarget::Host(String::from("127.0.0.1:6000"))
+            .addresses()
+            .unwrap(),
+        vec!["127.0.0.1:6000".parse::<SocketAddr>().unwrap()]
+    );
+}
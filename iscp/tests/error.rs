@@ -0,0 +1,100 @@
+use iscp::{Device, IscpError, IscpMessage, Remote};
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+fn receiver(reply: Option<&'static str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0; 1024];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            if let Some(parameter) = reply {
+                let message = IscpMessage::from("PWR", parameter).bytes().unwrap();
+                let _ = stream.write_all(message.as_ref());
+            }
+        }
+    });
+    address
+}
+
+#[test]
+fn reports_not_available_replies() {
+    let device = Device::from_address(&receiver(Some("N/A")));
+    match device.query("PWR") {
+        Err(e @ IscpError::NotAvailable(_)) => {
+            assert_eq!(e.to_string(), "PWR is not available on this device")
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn reports_query_timeouts() {
+    let device = Device::from_address(&receiver(None));
+    match device.query_timeout("PWR", Duration::from_millis(100)) {
+        Err(IscpError::Timeout) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn reports_connect_failures() {
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    match Device::from_address(&address).connect() {
+        Err(e @ IscpError::Connect(_)) => assert!(e.source().is_some()),
+        result => panic!("Unexpected result {:?}", result),
+    }
+    match Device::new().receive() {
+        Err(IscpError::NotConnected) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn reports_malformed_packets() {
+    match IscpMessage::from("PW", "01").bytes() {
+        Err(IscpError::MalformedPacket(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    match IscpMessage::from_slice(b"ISCP") {
+        Err(IscpError::MalformedPacket(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    let mut packet = IscpMessage::from("PWR", "01").bytes().unwrap().to_vec();
+    packet[18] = 0xFF;
+    match IscpMessage::from_slice(&packet) {
+        Err(IscpError::MalformedPacket(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn reports_config_errors() {
+    match Remote::deserialize("{\"devices\": [") {
+        Err(IscpError::Config(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn converts_io_errors() {
+    match IscpError::from(io::Error::from(io::ErrorKind::TimedOut)) {
+        IscpError::Timeout => {}
+        e => panic!("Unexpected error {:?}", e),
+    }
+    match IscpError::from(io::Error::from(io::ErrorKind::ConnectionReset)) {
+        e @ IscpError::Io(_) => assert!(e.source().is_some()),
+        e => panic!("Unexpected error {:?}", e),
+    }
+}
@@ -0,0 +1,151 @@
+use iscp::{
+    Band, DspFeature, DspLevel, Frequency, InputSource, IscpMessage, LevelChannel, ListeningMode,
+    PowerState, ReceiverEvent, Session, Speaker, Tone, Zone,
+};
+use std::io::Write;
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+fn event(command: &str, parameter: &str) -> ReceiverEvent {
+    ReceiverEvent::from_message(&IscpMessage::from(command, parameter))
+}
+
+#[test]
+fn decodes_zone_events() {
+    assert_eq!(
+        event("PWR", "01"),
+        ReceiverEvent::Power {
+            zone: Zone::Main,
+            state: PowerState::On
+        }
+    );
+    assert_eq!(
+        event("ZPW", "00"),
+        ReceiverEvent::Power {
+            zone: Zone::Zone2,
+            state: PowerState::Standby
+        }
+    );
+    assert_eq!(
+        event("VL3", "1E"),
+        ReceiverEvent::Volume {
+            zone: Zone::Zone3,
+            level: 30
+        }
+    );
+    assert_eq!(
+        event("MT4", "01"),
+        ReceiverEvent::Mute {
+            zone: Zone::Zone4,
+            muted: true
+        }
+    );
+    assert_eq!(
+        event("SLI", "2B"),
+        ReceiverEvent::Input {
+            zone: Zone::Main,
+            source: InputSource::Net
+        }
+    );
+}
+
+#[test]
+fn decodes_audio_events() {
+    assert_eq!(
+        event("LMD", "00"),
+        ReceiverEvent::ListeningMode(ListeningMode::Stereo)
+    );
+    assert_eq!(
+        event("TFR", "B-2T+4"),
+        ReceiverEvent::SpeakerTone {
+            speaker: Speaker::Front,
+            tone: Tone {
+                bass: -2,
+                treble: 4
+            }
+        }
+    );
+    assert_eq!(
+        event("TCT", "B+0T-A"),
+        ReceiverEvent::SpeakerTone {
+            speaker: Speaker::Center,
+            tone: Tone {
+                bass: 0,
+                treble: -10
+            }
+        }
+    );
+    assert_eq!(
+        event("SWL", "-F"),
+        ReceiverEvent::ChannelLevel {
+            channel: LevelChannel::Subwoofer,
+            level: -15
+        }
+    );
+    assert_eq!(
+        event("LTN", "02"),
+        ReceiverEvent::Dsp {
+            feature: DspFeature::LateNight,
+            level: DspLevel::High
+        }
+    );
+}
+
+#[test]
+fn decodes_tuner_events() {
+    assert_eq!(
+        event("TUN", "10150"),
+        ReceiverEvent::Tuner(Frequency {
+            band: Band::Fm,
+            khz: 101_500
+        })
+    );
+    assert_eq!(event("PRS", "1E"), ReceiverEvent::Preset(30));
+}
+
+#[test]
+fn falls_back_to_other_events() {
+    for (command, parameter) in &[
+        ("NTI", "Song"),
+        ("PWR", "N/A"),
+        ("MVL", "XX"),
+        ("SLI", "FE"),
+        ("LTN", "09"),
+        ("SWL", "QSTN"),
+    ] {
+        assert_eq!(
+            event(command, parameter),
+            ReceiverEvent::Other(IscpMessage::from(command, parameter))
+        );
+    }
+}
+
+#[test]
+fn registers_listeners_from_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let session = Arc::new(Session::connect(&address).unwrap());
+    let (mut stream, _) = listener.accept().unwrap();
+    let (sender, received) = mpsc::channel();
+    let weak: Weak<Session> = Arc::downgrade(&session);
+    session.add_listener(move |_| {
+        if let Some(session) = weak.upgrade() {
+            let sender = sender.clone();
+            session.add_listener(move |message| sender.send(message.clone()).is_ok());
+        }
+        false
+    });
+
+    for message in &[
+        IscpMessage::from("PWR", "01"),
+        IscpMessage::from("MVL", "20"),
+    ] {
+        stream.write_all(message.bytes().unwrap().as_ref()).unwrap();
+    }
+    assert_eq!(
+        received.recv_timeout(Duration::from_secs(2)).unwrap(),
+        IscpMessage::from("MVL", "20")
+    );
+}
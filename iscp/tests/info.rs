@@ -0,0 +1,135 @@
+use iscp::{Device, DeviceInfo, InputSource, IscpDecoder, IscpError, IscpMessage, Zone, ZoneInfo};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+static NRI_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<response status="ok">
+  <device id="TX-NR676">
+    <brand>ONKYO</brand>
+    <model>TX-NR676</model>
+    <firmwareversion>1000-1000-0000-0000</firmwareversion>
+    <zonelist count="3">
+      <zone id="1" value="1" name="Main" volmax="80" volstep="0"/>
+      <zone id="2" value="1" name="Zone2" volmax="60" volstep="1"/>
+      <zone id="3" value="0" name="Zone3" volmax="80" volstep="0"/>
+    </zonelist>
+    <selectorlist count="3">
+      <selector id="10" value="1" name="BD/DVD" zone="01" iconid="10"/>
+      <selector id="2B" value="1" name="NET" zone="07" iconid="2B"/>
+      <selector id="01" value="0" name="CBL/SAT" zone="03" iconid="01"/>
+    </selectorlist>
+    <presetlist count="3">
+      <preset id="01" band="1" freq="87.50" name="Radio One"/>
+      <preset id="0A" band="2" freq="1530" name=""/>
+      <preset id="02" band="0" freq="0" name=""/>
+    </presetlist>
+    <controllist>
+      <control id="Bass" value="1" zone="1" min="-10" max="10" step="2"/>
+      <control id="Center Level" value="0" zone="1" min="-12" max="12" step="1"/>
+    </controllist>
+  </device>
+</response>"#;
+
+fn receiver() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut decoder = IscpDecoder::new();
+        let mut buf = [0; 1024];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            decoder.feed(&buf[..n]);
+            while let Some(message) = decoder.decode() {
+                let reply = IscpMessage::from(&message.command, NRI_XML);
+                let _ = stream.write_all(reply.bytes().unwrap().as_ref());
+            }
+        }
+    });
+    address
+}
+
+#[test]
+fn parses_zone_list() {
+    let info = DeviceInfo::parse(NRI_XML).unwrap();
+    assert_eq!(info.brand, "ONKYO");
+    assert_eq!(info.model, "TX-NR676");
+    assert_eq!(
+        info.zones,
+        vec![
+            ZoneInfo {
+                zone: Zone::Main,
+                name: String::from("Main"),
+                volume_max: 80,
+                volume_step: 1.0,
+            },
+            ZoneInfo {
+                zone: Zone::Zone2,
+                name: String::from("Zone2"),
+                volume_max: 60,
+                volume_step: 0.5,
+            },
+        ]
+    );
+    assert!(info.supports_zone(Zone::Zone2));
+    assert!(!info.supports_zone(Zone::Zone3));
+}
+
+#[test]
+fn parses_selector_zone_mask() {
+    let info = DeviceInfo::parse(NRI_XML).unwrap();
+    assert_eq!(info.selectors.len(), 2);
+    assert_eq!(info.selectors[0].zones, vec![Zone::Main]);
+    assert_eq!(
+        info.selectors[1].zones,
+        vec![Zone::Main, Zone::Zone2, Zone::Zone3]
+    );
+    assert!(info.supports_input(Zone::Zone2, InputSource::Net));
+    assert!(!info.supports_input(Zone::Zone2, InputSource::BdDvd));
+    assert!(!info.supports_input(Zone::Main, InputSource::Video2));
+}
+
+#[test]
+fn parses_presets_and_controls() {
+    let info = DeviceInfo::parse(NRI_XML).unwrap();
+    assert_eq!(info.presets.len(), 2);
+    assert_eq!(info.presets[0].number, 1);
+    assert_eq!(info.presets[0].band, "FM");
+    assert_eq!(info.presets[0].frequency, "87.50");
+    assert_eq!(info.presets[0].name, "Radio One");
+    assert_eq!(info.presets[1].number, 10);
+    assert_eq!(info.presets[1].band, "AM");
+
+    let bass = info.control("bass").unwrap();
+    assert_eq!(bass.zone, Some(Zone::Main));
+    assert_eq!(
+        (bass.min, bass.max, bass.step),
+        (Some(-10), Some(10), Some(2))
+    );
+    assert!(info.control("Center Level").is_none());
+}
+
+#[test]
+fn rejects_failed_response() {
+    match DeviceInfo::parse(r#"<response status="fail"></response>"#) {
+        Err(IscpError::NotAvailable(command)) => assert_eq!(command, "NRI"),
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert!(DeviceInfo::parse(r#"<response status="ok"></response>"#).is_err());
+    assert!(DeviceInfo::parse("not xml").is_err());
+}
+
+#[test]
+fn refreshes_cached_info() {
+    let mut device = Device::from_address(&receiver());
+    device.info = Some(DeviceInfo {
+        model: String::from("stale"),
+        ..DeviceInfo::default()
+    });
+
+    assert_eq!(device.fetch_info().unwrap().model, "TX-NR676");
+    assert_eq!(device.info.as_ref().unwrap().zones.len(), 2);
+}
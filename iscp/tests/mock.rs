@@ -0,0 +1,107 @@
+use iscp::{
+    Device, DiscoveryReply, IscpError, IscpMessage, LevelChannel, MockReceiver, PowerState,
+    Speaker, Tone, ToneSetting, Zone,
+};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+fn receiver() -> MockReceiver {
+    let device = Device::from(
+        String::from("127.0.0.1:0"),
+        String::from("TX-SIM"),
+        String::from("XX"),
+        String::from("0009B0000001"),
+    );
+    MockReceiver::start(&device, Some("127.0.0.1:0")).unwrap()
+}
+
+#[test]
+fn applies_commands_over_tcp() {
+    let receiver = receiver();
+    let device = receiver.device();
+    let commands = device.commands();
+    let main = commands.main();
+    main.power_on().unwrap();
+    main.set_volume(0x2A).unwrap();
+    main.set_tone(Speaker::Center, ToneSetting::Bass, -4)
+        .unwrap();
+    main.set_channel_level(LevelChannel::Center, 0).unwrap();
+
+    assert_eq!(main.power_state().unwrap(), PowerState::On);
+    assert_eq!(main.volume().unwrap(), 0x2A);
+    assert_eq!(
+        main.tone(Speaker::Center).unwrap(),
+        Tone {
+            bass: -4,
+            treble: 0
+        }
+    );
+    assert_eq!(receiver.power(Zone::Main), Some(PowerState::On));
+    assert_eq!(receiver.volume(Zone::Main), Some(0x2A));
+    assert_eq!(receiver.get("TCT").as_deref(), Some("B-4T00"));
+    assert_eq!(receiver.get("CTL").as_deref(), Some("00"));
+    assert_eq!(receiver.channel_level(LevelChannel::Center), Some(0));
+}
+
+#[test]
+fn parses_signed_levels_over_tcp() {
+    let receiver = receiver();
+    let device = receiver.device();
+    device.connect().unwrap();
+    device.raw("CTL", "+3").unwrap();
+    device.raw("CTL", "+0").unwrap();
+    device.raw("SWL", "-A").unwrap();
+    device.raw("SWL", "00").unwrap();
+    device.raw("TCT", "B+2").unwrap();
+    device.raw("TCT", "B+-5").unwrap();
+    assert_eq!(
+        device.query("TCT").unwrap(),
+        IscpMessage::from("TCT", "B+2T00")
+    );
+
+    assert_eq!(receiver.channel_level(LevelChannel::Center), Some(0));
+    assert_eq!(receiver.get("CTL").as_deref(), Some("00"));
+    assert_eq!(receiver.channel_level(LevelChannel::Subwoofer), Some(0));
+}
+
+#[test]
+fn answers_queries_over_tcp() {
+    let receiver = receiver();
+    receiver.set("MVL", "1E");
+    let device = receiver.device();
+
+    assert_eq!(device.query("MVL").unwrap(), IscpMessage::from("MVL", "1E"));
+    match device.query("NTI") {
+        Err(IscpError::NotAvailable(command)) => assert_eq!(command, "NTI"),
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert!(receiver
+        .received()
+        .contains(&IscpMessage::from("NTI", "QSTN")));
+
+    let taken = receiver.take_received();
+    assert!(taken.contains(&IscpMessage::from("MVL", "QSTN")));
+    assert!(receiver.received().is_empty());
+}
+
+#[test]
+fn answers_discovery_over_udp() {
+    let receiver = receiver();
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let query = IscpMessage::from("ECN", "QSTN").bytes().unwrap();
+    socket
+        .send_to(query.as_ref(), receiver.discovery_address().unwrap())
+        .unwrap();
+
+    let mut buf = [0; 1024];
+    let (n, source) = socket.recv_from(&mut buf).unwrap();
+    let reply = DiscoveryReply::parse(&buf[..n], source).unwrap();
+    assert_eq!(reply.model, "TX-SIM");
+    assert_eq!(reply.port, receiver.address().port());
+    assert_eq!(reply.area, "XX");
+    assert_eq!(reply.mac, "0009B0000001");
+    assert_eq!(reply.address(), receiver.address());
+}
@@ -0,0 +1,66 @@
+use iscp::{InputSource, IscpError, ListeningMode};
+
+#[test]
+fn parses_input_source_names() {
+    for source in InputSource::all() {
+        assert_eq!(InputSource::from_code(source.code()), Some(source));
+        assert_eq!(source.name().parse::<InputSource>().unwrap(), source);
+        assert_eq!(source.to_string().parse::<InputSource>().unwrap(), source);
+    }
+}
+
+#[test]
+fn parses_input_source_aliases() {
+    assert_eq!("bd/dvd".parse::<InputSource>().unwrap(), InputSource::BdDvd);
+    assert_eq!("Bd Dvd".parse::<InputSource>().unwrap(), InputSource::BdDvd);
+    assert_eq!("DVD".parse::<InputSource>().unwrap(), InputSource::BdDvd);
+    assert_eq!(
+        "cbl/sat".parse::<InputSource>().unwrap(),
+        InputSource::Video2
+    );
+    assert_eq!("game".parse::<InputSource>().unwrap(), InputSource::Video3);
+    assert_eq!("bt".parse::<InputSource>().unwrap(), InputSource::Bluetooth);
+    assert_eq!(
+        "usb-rear".parse::<InputSource>().unwrap(),
+        InputSource::UsbRear
+    );
+}
+
+#[test]
+fn rejects_unknown_input_source() {
+    match "cassette".parse::<InputSource>() {
+        Err(IscpError::InvalidArgument(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert!("".parse::<InputSource>().is_err());
+    assert_eq!(InputSource::from_code(0xFE), None);
+}
+
+#[test]
+fn parses_listening_mode_names() {
+    for mode in ListeningMode::all() {
+        assert_eq!(ListeningMode::from_code(mode.code()), Some(mode));
+        assert_eq!(mode.name().parse::<ListeningMode>().unwrap(), mode);
+    }
+}
+
+#[test]
+fn parses_listening_mode_aliases() {
+    assert_eq!(
+        "pure direct".parse::<ListeningMode>().unwrap(),
+        ListeningMode::PureAudio
+    );
+    assert_eq!(
+        "Dolby Surround".parse::<ListeningMode>().unwrap(),
+        ListeningMode::PlMovie
+    );
+    assert_eq!(
+        "pliix-music".parse::<ListeningMode>().unwrap(),
+        ListeningMode::PlMusic
+    );
+    assert_eq!(
+        "auto".parse::<ListeningMode>().unwrap(),
+        ListeningMode::AutoSurround
+    );
+    assert!("karaoke".parse::<ListeningMode>().is_err());
+}
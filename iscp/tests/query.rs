@@ -0,0 +1,111 @@
+use iscp::{Device, IscpDecoder, IscpError, IscpMessage};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn receiver<F>(respond: F) -> String
+where
+    F: Fn(&IscpMessage) -> Vec<IscpMessage> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut decoder = IscpDecoder::new();
+        let mut buf = [0; 1024];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            decoder.feed(&buf[..n]);
+            while let Some(message) = decoder.decode() {
+                for reply in respond(&message) {
+                    let _ = stream.write_all(reply.bytes().unwrap().as_ref());
+                }
+            }
+        }
+    });
+    address
+}
+
+fn answering(answers: &'static [(&'static str, &'static str)]) -> String {
+    receiver(move |message| {
+        if message.parameter != "QSTN" {
+            return vec![message.clone()];
+        }
+        answers
+            .iter()
+            .filter(|(command, _)| *command == message.command)
+            .map(|(command, parameter)| IscpMessage::from(command, parameter))
+            .collect()
+    })
+}
+
+#[test]
+fn skips_unrelated_messages_while_querying() {
+    let device = Device::from_address(&receiver(|message| {
+        vec![
+            IscpMessage::from("NTI", "Song"),
+            IscpMessage::from("MVL", "20"),
+            IscpMessage::from(&message.command, "01"),
+        ]
+    }));
+
+    assert_eq!(device.query("PWR").unwrap(), IscpMessage::from("PWR", "01"));
+}
+
+#[test]
+fn times_out_without_matching_reply() {
+    let device = Device::from_address(&receiver(|_| vec![IscpMessage::from("MVL", "20")]));
+
+    match device.query_timeout("PWR", Duration::from_millis(100)) {
+        Err(IscpError::Timeout) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn keeps_pending_messages_while_querying() {
+    let device = Device::from_address(&answering(&[("PWR", "01")]));
+    device.connect().unwrap();
+    device.raw("NTI", "Song").unwrap();
+    thread::sleep(Duration::from_millis(200));
+
+    assert_eq!(device.query("PWR").unwrap(), IscpMessage::from("PWR", "01"));
+    let timeout = Duration::from_secs(2);
+    assert_eq!(
+        device.receive_timeout(timeout).unwrap(),
+        IscpMessage::from("NTI", "Song")
+    );
+    assert_eq!(
+        device.receive_timeout(timeout).unwrap(),
+        IscpMessage::from("PWR", "01")
+    );
+}
+
+#[test]
+fn answers_concurrent_queries() {
+    let device = Arc::new(Device::from_address(&answering(&[
+        ("PWR", "01"),
+        ("MVL", "2A"),
+    ])));
+    let threads: Vec<_> = [("PWR", "01"), ("MVL", "2A")]
+        .iter()
+        .map(|&(command, parameter)| {
+            let device = Arc::clone(&device);
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    assert_eq!(
+                        device.query(command).unwrap(),
+                        IscpMessage::from(command, parameter)
+                    );
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use iscp::{Device, IscpError, Remote};
+
+fn device(address: &str, model: &str, mac: &str) -> Device {
+    Device::from(
+        String::from(address),
+        String::from(model),
+        String::from("XX"),
+        String::from(mac),
+    )
+}
+
+fn remote() -> Remote {
+    let mut remote = Remote::new();
+    remote.merge(vec![
+        device("192.168.1.20:60128", "TX-NR676", "0009B0123456"),
+        device("192.168.1.30:60128", "TX-SIM", ""),
+    ]);
+    remote
+}
+
+#[test]
+fn finds_devices_by_mac_address() {
+    let remote = remote();
+    assert_eq!(remote.device("0009B0123456").unwrap().model, "TX-NR676");
+    assert_eq!(
+        remote.device("00:09:b0:12:34:56").unwrap().model,
+        "TX-NR676"
+    );
+    assert_eq!(
+        remote.device("00-09-B0-12-34-56").unwrap().model,
+        "TX-NR676"
+    );
+    assert_eq!(remote.device("192.168.1.30:60128").unwrap().model, "TX-SIM");
+    assert_eq!(
+        remote.device(" 192.168.1.30:60128 ").unwrap().identifier(),
+        "192.168.1.30:60128"
+    );
+    assert!(remote.device("0009B0000000").is_none());
+    assert!(remote.device("0").is_none());
+    assert!(remote.device("1").is_none());
+}
+
+#[test]
+fn finds_devices_by_alias() {
+    let mut remote = remote();
+    remote
+        .set_alias("0009B0123456", Some("LivingRoom"))
+        .unwrap();
+    remote
+        .set_alias("192.168.1.30:60128", Some("kitchen"))
+        .unwrap();
+
+    assert_eq!(remote.device("livingroom").unwrap().model, "TX-NR676");
+    assert_eq!(remote.device("KITCHEN").unwrap().model, "TX-SIM");
+
+    remote.set_alias("kitchen", None).unwrap();
+    assert!(remote.device("kitchen").is_none());
+    assert_eq!(remote.devices[1].alias, None);
+}
+
+#[test]
+fn finds_devices_by_numeric_alias() {
+    let mut remote = remote();
+    remote.set_alias("192.168.1.30:60128", Some("0")).unwrap();
+    remote.set_alias("0009B0123456", Some("1")).unwrap();
+
+    assert_eq!(remote.device("0").unwrap().model, "TX-SIM");
+    assert_eq!(remote.device("1").unwrap().model, "TX-NR676");
+}
+
+#[test]
+fn rejects_invalid_aliases() {
+    let mut remote = remote();
+    remote.set_alias("0009B0123456", Some("den")).unwrap();
+    for alias in &["", "den", "0009b0123456"] {
+        match remote.set_alias("192.168.1.30:60128", Some(alias)) {
+            Err(IscpError::InvalidArgument(_)) => {}
+            result => panic!("Unexpected result for {:?}: {:?}", alias, result),
+        }
+    }
+    assert!(remote.set_alias("0009B0123456", Some("den")).is_ok());
+    assert!(remote.set_alias("unknown", Some("attic")).is_err());
+}
+
+#[test]
+fn merges_rediscovered_device_with_new_address() {
+    let mut remote = remote();
+    remote.set_alias("0009B0123456", Some("den")).unwrap();
+    remote.merge(vec![
+        device("192.168.1.99:60128", "", "00:09:B0:12:34:56"),
+        device("192.168.1.30:60128", "TX-SIM2", "0009B0000001"),
+        device("192.168.1.40:60128", "TX-RZ50", "0009B0ABCDEF"),
+    ]);
+
+    assert_eq!(remote.devices.len(), 3);
+    let den = remote.device("den").unwrap();
+    assert_eq!(den.address, "192.168.1.99:60128");
+    assert_eq!(den.model, "TX-NR676");
+    assert_eq!(den.mac, "0009B0123456");
+
+    let sim = remote.device("192.168.1.30:60128").unwrap();
+    assert_eq!(sim.model, "TX-SIM2");
+    assert_eq!(sim.mac, "0009B0000001");
+    assert_eq!(remote.device("0009B0ABCDEF").unwrap().model, "TX-RZ50");
+}
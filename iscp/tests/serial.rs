@@ -0,0 +1,112 @@
+use iscp::{IscpError, Link, SERIAL_DEFAULT_BAUD_RATE};
+
+#[test]
+fn parses_serial_links() {
+    assert_eq!(
+        "serial:/dev/x@9600".parse::<Link>().unwrap(),
+        Link::Serial {
+            path: String::from("/dev/x"),
+            baud_rate: 9600
+        }
+    );
+    assert_eq!(
+        "serial:COM3".parse::<Link>().unwrap(),
+        Link::Serial {
+            path: String::from("COM3"),
+            baud_rate: SERIAL_DEFAULT_BAUD_RATE
+        }
+    );
+    assert_eq!(
+        "192.168.1.20:60128".parse::<Link>().unwrap(),
+        Link::Tcp(String::from("192.168.1.20:60128"))
+    );
+    let link = "serial:/dev/ttyUSB0@19200".parse::<Link>().unwrap();
+    assert_eq!(link.to_string(), "serial:/dev/ttyUSB0@19200");
+}
+
+#[test]
+fn rejects_invalid_serial_links() {
+    for link in &[
+        "serial:/dev/x@fast",
+        "serial:/dev/x@",
+        "serial:/dev/x@-1",
+        "serial:@9600",
+        "serial:",
+    ] {
+        match link.parse::<Link>() {
+            Err(IscpError::InvalidArgument(_)) => {}
+            result => panic!("Unexpected result for {:?}: {:?}", link, result),
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "serial"))]
+#[test]
+fn exchanges_messages_over_pseudo_terminal() {
+    use iscp::{Device, IscpMessage};
+    use serialport::{SerialPort, TTYPort};
+    use std::io::{Read, Write};
+    use std::thread;
+    use std::time::Duration;
+
+    let (mut master, slave) = TTYPort::pair().unwrap();
+    let path = slave.name().unwrap();
+    drop(slave);
+    master.set_timeout(Duration::from_secs(2)).unwrap();
+
+    let device = Device::from_serial(&path, 9600);
+    device.connect().unwrap();
+    let receiver = thread::spawn(move || {
+        let mut received = Vec::new();
+        let mut buf = [0; 64];
+        while !received.ends_with(b"!1MVLQSTN\r") {
+            let n = master.read(&mut buf).unwrap();
+            received.extend_from_slice(&buf[..n]);
+        }
+        master
+            .write_all(b"!1NTISong\x1a\r\n!1MVL2A\x1a\r\n")
+            .unwrap();
+        (master, received)
+    });
+
+    device.commands().main().power_on().unwrap();
+    assert_eq!(device.query("MVL").unwrap(), IscpMessage::from("MVL", "2A"));
+    let (_master, received) = receiver.join().unwrap();
+    assert_eq!(received, b"!1PWR01\r!1MVLQSTN\r");
+}
+
+#[cfg(all(unix, feature = "serial"))]
+#[test]
+fn wakes_reader_when_serial_transport_closes() {
+    use iscp::{IscpMessage, SerialTransport, Transport};
+    use serialport::{SerialPort, TTYPort};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    let (_master, slave) = TTYPort::pair().unwrap();
+    let path = slave.name().unwrap();
+    drop(slave);
+
+    let transport = Arc::new(SerialTransport::open(&path, 9600).unwrap());
+    let reader_transport = Arc::clone(&transport);
+    let reader = thread::spawn(move || {
+        let started = Instant::now();
+        let result = reader_transport.receive_timeout(Duration::from_secs(5));
+        (result, started.elapsed())
+    });
+    thread::sleep(Duration::from_millis(100));
+    transport.close();
+
+    let (result, elapsed) = reader.join().unwrap();
+    match result {
+        Err(IscpError::NotConnected) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert!(elapsed < Duration::from_secs(1));
+    match transport.send(&IscpMessage::from("PWR", "01")) {
+        Err(IscpError::NotConnected) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert_eq!(transport.address(), format!("serial:{}@9600", path));
+}
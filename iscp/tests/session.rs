@@ -0,0 +1,166 @@
+use iscp::{Device, IscpError, IscpMessage, MemoryTransport, Transport};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn volume(level: usize) -> IscpMessage {
+    IscpMessage::from("MVL", &format!("{:02X}", level))
+}
+
+fn text(messages: Vec<IscpMessage>) -> Vec<String> {
+    messages
+        .into_iter()
+        .map(|message| format!("{}{}", message.command, message.parameter))
+        .collect()
+}
+
+fn receiver() -> (String, Receiver<TcpStream>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (sender, connections) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if sender.send(stream.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    (address, connections)
+}
+
+fn read_messages(stream: &mut TcpStream, count: usize) -> Vec<IscpMessage> {
+    let mut messages = Vec::new();
+    while messages.len() < count {
+        let mut header = [0; 16];
+        stream.read_exact(&mut header).unwrap();
+        let len_payload = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+        let mut packet = vec![0; len_payload as usize];
+        stream.read_exact(&mut packet).unwrap();
+        packet.splice(0..0, header.iter().cloned());
+        messages.push(IscpMessage::from_slice(&packet).unwrap());
+    }
+    messages
+}
+
+#[test]
+fn reuses_session_for_commands() {
+    let (address, connections) = receiver();
+    let device = Device::from_address(&address);
+    device.connect().unwrap();
+    device.raw("PWR", "01").unwrap();
+    device.raw("MVL", "UP").unwrap();
+
+    let timeout = Duration::from_secs(2);
+    let mut stream = connections.recv_timeout(timeout).unwrap();
+    assert!(device.is_connected());
+    assert_eq!(text(read_messages(&mut stream, 2)), vec!["PWR01", "MVLUP"]);
+
+    stream
+        .write_all(IscpMessage::from("PWR", "01").bytes().unwrap().as_ref())
+        .unwrap();
+    stream
+        .write_all(IscpMessage::from("AMT", "00").bytes().unwrap().as_ref())
+        .unwrap();
+    let received = vec![
+        device.receive_timeout(timeout).unwrap(),
+        device.receive_timeout(timeout).unwrap(),
+    ];
+    assert_eq!(text(received), vec!["PWR01", "AMT00"]);
+    assert!(connections.try_recv().is_err());
+}
+
+#[test]
+fn reconnects_after_disconnect() {
+    let (address, connections) = receiver();
+    let device = Device::from_address(&address);
+    let timeout = Duration::from_secs(2);
+    device.connect().unwrap();
+    let mut first = connections.recv_timeout(timeout).unwrap();
+
+    device.disconnect();
+    assert!(!device.is_connected());
+    assert!(device.receive_timeout(Duration::from_millis(100)).is_err());
+    let mut buf = [0; 16];
+    assert_eq!(first.read(&mut buf).unwrap(), 0);
+
+    device.connect().unwrap();
+    device.raw("PWR", "00").unwrap();
+    let mut second = connections.recv_timeout(timeout).unwrap();
+    assert_eq!(text(read_messages(&mut second, 1)), vec!["PWR00"]);
+}
+
+#[test]
+fn reconnects_with_new_transport() {
+    let first = Arc::new(MemoryTransport::new());
+    let device = Device::from_transport(first.clone());
+    device.raw("PWR", "01").unwrap();
+
+    device.disconnect();
+    assert!(!device.is_connected());
+    match first.send(&IscpMessage::from("PWR", "00")) {
+        Err(IscpError::NotConnected) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    match device.receive_timeout(Duration::from_millis(100)) {
+        Err(IscpError::NotConnected) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let second = Arc::new(MemoryTransport::new());
+    device.attach(second.clone());
+    device.raw("PWR", "00").unwrap();
+    second.push(IscpMessage::from("PWR", "00"));
+
+    assert_eq!(
+        device.receive_timeout(Duration::from_secs(2)).unwrap(),
+        IscpMessage::from("PWR", "00")
+    );
+    assert_eq!(first.sent(), vec![IscpMessage::from("PWR", "01")]);
+    assert_eq!(second.sent(), vec![IscpMessage::from("PWR", "00")]);
+}
+
+#[test]
+fn disconnects_from_listener() {
+    let (address, connections) = receiver();
+    let device = Arc::new(Device::from_address(&address));
+    let (sender, disconnected) = mpsc::channel();
+    let listener_device = Arc::clone(&device);
+    device
+        .on_event(move |_| {
+            listener_device.disconnect();
+            sender.send(()).is_ok()
+        })
+        .unwrap();
+
+    let timeout = Duration::from_secs(2);
+    let mut stream = connections.recv_timeout(timeout).unwrap();
+    stream
+        .write_all(IscpMessage::from("PWR", "00").bytes().unwrap().as_ref())
+        .unwrap();
+    disconnected.recv_timeout(timeout).unwrap();
+    assert!(!device.is_connected());
+    let mut buf = [0; 16];
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn keeps_newest_messages_when_inbox_is_full() {
+    let (address, connections) = receiver();
+    let device = Device::from_address(&address);
+    device.connect().unwrap();
+    let mut stream = connections.recv_timeout(Duration::from_secs(2)).unwrap();
+    for level in 0..80 {
+        stream.write_all(volume(level).bytes().unwrap().as_ref()).unwrap();
+    }
+    drop(stream);
+    thread::sleep(Duration::from_millis(500));
+
+    let mut received = Vec::new();
+    while let Ok(message) = device.receive_timeout(Duration::from_millis(100)) {
+        received.push(message);
+    }
+    assert_eq!(text(received), text((16..80).map(volume).collect()));
+}
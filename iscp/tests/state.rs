@@ -0,0 +1,224 @@
+use iscp::{
+    AlbumArt, ArtAssembler, Band, Device, Frequency, ImageType, InputSource, IscpMessage,
+    MemoryTransport, NowPlaying, PlayStatus, ProgramType, RdsDisplay, RdsInfo, RepeatMode,
+    ShuffleMode,
+};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn hex(text: &str) -> String {
+    text.bytes().map(|b| format!("{:02X}", b)).collect()
+}
+
+#[test]
+fn decodes_rds_notifications() {
+    let mut rds = RdsInfo::default();
+    assert!(rds.update(&IscpMessage::from("SLI", "24")));
+    assert!(rds.update(&IscpMessage::from("TUN", "08810")));
+    assert!(rds.update(&IscpMessage::from("RDS", "00")));
+    assert!(rds.update(&IscpMessage::from("TPS", &hex("Now playing..."))));
+    assert!(!rds.update(&IscpMessage::from("FLD", &hex("Now playing..."))));
+    assert!(!rds.update(&IscpMessage::from("PTS", "0A")));
+    assert!(!rds.update(&IscpMessage::from("TPS", "ENTER")));
+    assert!(!rds.update(&IscpMessage::from("MVL", "20")));
+
+    assert_eq!(
+        rds,
+        RdsInfo {
+            input: Some(InputSource::Fm),
+            frequency: Some(Frequency {
+                band: Band::Fm,
+                khz: 88_100
+            }),
+            display: Some(RdsDisplay::RadioText),
+            active: true,
+            station: None,
+            radio_text: Some(String::from("Now playing...")),
+            program_type: None,
+        }
+    );
+    assert_eq!(rds.to_string(), "FM 88.10 MHz \u{2013} Now playing...");
+
+    assert!(rds.update(&IscpMessage::from("RDS", "01")));
+    assert!(rds.update(&IscpMessage::from("FLD", &hex("Pop Music"))));
+    assert_eq!(rds.program_type, Some(ProgramType::PopMusic));
+
+    assert!(rds.update(&IscpMessage::from("RDS", "N/A")));
+    assert!(!rds.active);
+    assert_eq!(rds.radio_text, None);
+    assert_eq!(rds.program_type, None);
+    assert_eq!(rds.display, Some(RdsDisplay::ProgramType));
+}
+
+#[test]
+fn reads_station_name_without_rds_display() {
+    let mut rds = RdsInfo::default();
+    assert!(rds.update(&IscpMessage::from("SLI", "24")));
+    assert!(rds.update(&IscpMessage::from("TUN", "08810")));
+    assert!(!rds.update(&IscpMessage::from("FLD", &hex("FM  88.10MHz"))));
+    assert_eq!(rds.station, None);
+
+    assert!(rds.update(&IscpMessage::from("TPS", "Now playing...")));
+    assert_eq!(rds.display, None);
+    assert!(rds.update(&IscpMessage::from("FLD", &hex("BBC R2  "))));
+    assert_eq!(rds.station.as_deref(), Some("BBC R2"));
+    assert_eq!(rds.to_string(), "BBC R2 \u{2013} Now playing...");
+
+    assert!(rds.update(&IscpMessage::from("TUN", "09050")));
+    assert!(!rds.active);
+    assert_eq!(rds.station, None);
+    assert_eq!(rds.radio_text, None);
+    assert!(!rds.update(&IscpMessage::from("FLD", &hex("FM  90.50MHz"))));
+    assert_eq!(rds.station, None);
+}
+
+#[test]
+fn ignores_display_text_outside_tuner() {
+    let mut rds = RdsInfo::default();
+    assert!(!rds.update(&IscpMessage::from("FLD", &hex("HDMI 1"))));
+    assert_eq!(rds.station, None);
+
+    assert!(rds.update(&IscpMessage::from("SLI", "24")));
+    assert!(rds.update(&IscpMessage::from("TUN", "09050")));
+    assert!(rds.update(&IscpMessage::from("TPS", "News at ten")));
+    assert!(rds.update(&IscpMessage::from("FLD", &hex("TALK 905"))));
+    assert_eq!(rds.station.as_deref(), Some("TALK 905"));
+
+    assert!(rds.update(&IscpMessage::from("SLI", "10")));
+    assert_eq!(rds.input, Some(InputSource::from_code(0x10).unwrap()));
+    assert_eq!(rds.frequency, None);
+    assert_eq!(rds.station, None);
+    assert!(!rds.active);
+    assert!(!rds.update(&IscpMessage::from("FLD", &hex("BD/DVD"))));
+    assert_eq!(rds.station, None);
+}
+
+#[test]
+fn delivers_rds_updates_to_connected_device() {
+    let transport = Arc::new(MemoryTransport::new());
+    let device = Device::from_transport(transport.clone());
+    let (sender, updates) = mpsc::channel();
+    device.on_rds(move |rds| sender.send(rds).is_ok()).unwrap();
+
+    transport.push(IscpMessage::from("SLI", "24"));
+    let rds = updates.recv_timeout(Duration::from_secs(2)).unwrap();
+    assert_eq!(rds.input, Some(InputSource::Fm));
+    transport.push(IscpMessage::from("RDS", "00"));
+    let rds = updates.recv_timeout(Duration::from_secs(2)).unwrap();
+    assert!(rds.active);
+    transport.push(IscpMessage::from("TPS", "Morning show"));
+    let rds = updates.recv_timeout(Duration::from_secs(2)).unwrap();
+    assert_eq!(rds.radio_text.as_deref(), Some("Morning show"));
+    assert_eq!(device.rds().unwrap(), rds);
+}
+
+#[test]
+fn decodes_now_playing_notifications() {
+    let mut now_playing = NowPlaying::default();
+    assert!(now_playing.update(&IscpMessage::from("NTI", "Karma Police")));
+    assert!(now_playing.update(&IscpMessage::from("NAT", "Radiohead")));
+    assert!(now_playing.update(&IscpMessage::from("NAL", "OK Computer")));
+    assert!(now_playing.update(&IscpMessage::from("NTM", "01:05/04:21")));
+    assert!(now_playing.update(&IscpMessage::from("NTR", "0006/0012")));
+    assert!(now_playing.update(&IscpMessage::from("NST", "P-S")));
+    assert!(!now_playing.update(&IscpMessage::from("NTI", "N/A")));
+
+    assert_eq!(
+        now_playing,
+        NowPlaying {
+            title: Some(String::from("Karma Police")),
+            artist: Some(String::from("Radiohead")),
+            album: Some(String::from("OK Computer")),
+            elapsed: Some(Duration::from_secs(65)),
+            total: Some(Duration::from_secs(261)),
+            track: Some(6),
+            track_count: Some(12),
+            status: Some(PlayStatus::Playing),
+            repeat: Some(RepeatMode::Off),
+            shuffle: Some(ShuffleMode::All),
+        }
+    );
+
+    assert!(now_playing.update(&IscpMessage::from("NTM", "--:--/1:02:03")));
+    assert_eq!(now_playing.elapsed, None);
+    assert_eq!(now_playing.total, Some(Duration::from_secs(3723)));
+}
+
+#[test]
+fn fetches_now_playing_on_demand() {
+    let transport = Arc::new(MemoryTransport::with_responder(|message| {
+        let parameter = match message.command.as_str() {
+            "NTI" => "Song",
+            "NAT" => "Artist",
+            "NTR" => "0001/0002",
+            "NST" => "p1-",
+            _ => "N/A",
+        };
+        vec![IscpMessage::from(&message.command, parameter)]
+    }));
+    let device = Device::from_transport(transport.clone());
+    let now_playing = device.commands().net().now_playing().unwrap();
+
+    assert_eq!(now_playing.title.as_deref(), Some("Song"));
+    assert_eq!(now_playing.album, None);
+    assert_eq!(now_playing.track_count, Some(2));
+    assert_eq!(now_playing.status, Some(PlayStatus::Paused));
+    assert_eq!(now_playing.repeat, Some(RepeatMode::One));
+    assert_eq!(device.now_playing().unwrap(), now_playing);
+}
+
+#[test]
+fn assembles_album_art_packets() {
+    let mut art = ArtAssembler::default();
+    assert!(!art.update(&IscpMessage::from("NJA", "10FFD8")));
+    assert!(!art.update(&IscpMessage::from("NJA", "11FFE0")));
+    assert!(art.update(&IscpMessage::from("NJA", "12FFD9")));
+    assert_eq!(
+        art.art(),
+        Some(&AlbumArt::Image {
+            image_type: ImageType::Jpeg,
+            data: vec![0xFF, 0xD8, 0xFF, 0xE0, 0xFF, 0xD9],
+        })
+    );
+    assert_eq!(art.art().unwrap().mime_type(), Some("image/jpeg"));
+
+    assert!(!art.update(&IscpMessage::from("NJA", "00424D")));
+    assert!(!art.update(&IscpMessage::from("NJA", "01XYZ")));
+    assert!(!art.update(&IscpMessage::from("NJA", "0200")));
+    assert_eq!(art.art().unwrap().mime_type(), Some("image/jpeg"));
+
+    assert!(art.update(&IscpMessage::from("NJA", "2-http://10.0.0.2/art.jpg")));
+    assert_eq!(
+        art.art(),
+        Some(&AlbumArt::Link(String::from("http://10.0.0.2/art.jpg")))
+    );
+
+    assert!(art.update(&IscpMessage::from("NJA", "n-")));
+    assert_eq!(art.art(), None);
+}
+
+#[test]
+fn requests_album_art_and_caches_it() {
+    let transport = Arc::new(MemoryTransport::with_responder(|message| {
+        match (message.command.as_str(), message.parameter.as_str()) {
+            ("NJA", "REQ") => vec![
+                IscpMessage::from("NJA", "00424D"),
+                IscpMessage::from("MVL", "20"),
+                IscpMessage::from("NJA", "020102"),
+            ],
+            _ => vec![],
+        }
+    }));
+    let device = Device::from_transport(transport.clone());
+    let art = device.commands().net().album_art().unwrap();
+
+    assert_eq!(
+        art,
+        AlbumArt::Image {
+            image_type: ImageType::Bmp,
+            data: vec![0x42, 0x4D, 0x01, 0x02],
+        }
+    );
+    assert_eq!(device.album_art().unwrap(), Some(art));
+}
@@ -0,0 +1,81 @@
+use iscp::{Device, IscpDecoder, IscpError, IscpMessage, PowerState, Zone};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+fn receiver() -> (String, Receiver<IscpMessage>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (sender, received) = mpsc::channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut decoder = IscpDecoder::new();
+        let mut buf = [0; 1024];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            decoder.feed(&buf[..n]);
+            while let Some(message) = decoder.decode() {
+                if message.parameter == "QSTN" {
+                    let reply = IscpMessage::from(&message.command, "01");
+                    let _ = stream.write_all(reply.bytes().unwrap().as_ref());
+                }
+                let _ = sender.send(message);
+            }
+        }
+    });
+    (address, received)
+}
+
+#[test]
+fn parses_zone_names() {
+    assert_eq!("main".parse::<Zone>().unwrap(), Zone::Main);
+    assert_eq!("Main".parse::<Zone>().unwrap(), Zone::Main);
+    assert_eq!("1".parse::<Zone>().unwrap(), Zone::Main);
+    assert_eq!("2".parse::<Zone>().unwrap(), Zone::Zone2);
+    assert_eq!("zone3".parse::<Zone>().unwrap(), Zone::Zone3);
+    assert_eq!("Zone 4".parse::<Zone>().unwrap(), Zone::Zone4);
+    for zone in Zone::all() {
+        assert_eq!(zone.to_string().parse::<Zone>().unwrap(), zone);
+        assert_eq!(Zone::from_number(zone.number()), Some(zone));
+    }
+}
+
+#[test]
+fn rejects_unknown_zone() {
+    match "zone5".parse::<Zone>() {
+        Err(IscpError::InvalidArgument(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    assert!("0".parse::<Zone>().is_err());
+    assert!("zone".parse::<Zone>().is_err());
+}
+
+#[test]
+fn controls_main_zone_without_trait_import() {
+    let (address, received) = receiver();
+    let device = Device::from_address(&address);
+    device.connect().unwrap();
+    let commands = device.commands();
+    let main = commands.main();
+    main.power_on().unwrap();
+    main.set_volume(20).unwrap();
+    main.unmute().unwrap();
+
+    assert_eq!(main.power_state().unwrap(), PowerState::On);
+    let timeout = Duration::from_secs(2);
+    assert_eq!(
+        (0..4)
+            .map(|_| received.recv_timeout(timeout).unwrap())
+            .collect::<Vec<_>>(),
+        vec![
+            IscpMessage::from("PWR", "01"),
+            IscpMessage::from("MVL", "14"),
+            IscpMessage::from("AMT", "00"),
+            IscpMessage::from("PWR", "QSTN"),
+        ]
+    );
+}
@@ -20,22 +20,66 @@ struct ProgramOptions {
 #[derive(StructOpt, PartialEq)]
 enum Command {
     #[structopt(about = "Discovers available devices in the network")]
-    Discover,
+    Discover {
+        #[structopt(long, default_value = "5", help = "Receive timeout in seconds")]
+        timeout: u64,
+        #[structopt(long, help = "Stops after this number of devices answered")]
+        limit: Option<usize>,
+        #[structopt(
+            long,
+            help = "Stops when no device answered for this number of seconds"
+        )]
+        quiet: Option<u64>,
+        #[structopt(
+            long = "target",
+            use_delimiter = true,
+            help = "Host, address or network to query instead of broadcasting (e.g. 192.168.10.0/24)"
+        )]
+        targets: Vec<iscp::DiscoverTarget>,
+        #[structopt(long, help = "Probes the targets on TCP port 60128 as well")]
+        probe: bool,
+    },
     #[structopt(about = "Lists the discovered devices")]
     List,
     #[structopt(about = "Selects a device")]
     Select {
-        #[structopt(help = "ID from device list")]
-        id: usize,
+        #[structopt(help = "Alias or MAC address from device list")]
+        id: String,
+    },
+    #[structopt(about = "Assigns an alias to a device")]
+    Alias {
+        #[structopt(help = "Alias or MAC address from device list")]
+        id: String,
+        #[structopt(help = "New alias (e.g. livingroom), removes the alias if omitted")]
+        alias: Option<String>,
+    },
+    #[structopt(about = "Fetches and shows the capabilities of the selected device")]
+    Info,
+    #[structopt(about = "Shows the current status of the selected device")]
+    Status {
+        #[structopt(long, help = "Shows the track playing on the network player instead")]
+        now_playing: bool,
     },
     #[structopt(about = "Powers on the selected device")]
-    On,
+    On {
+        #[structopt(long, default_value = "main", help = "Zone (Main, 2, 3, 4)")]
+        zone: iscp::Zone,
+    },
     #[structopt(about = "Powers off the selected device")]
-    Off,
+    Off {
+        #[structopt(long, default_value = "main", help = "Zone (Main, 2, 3, 4)")]
+        zone: iscp::Zone,
+    },
     #[structopt(about = "Mutes the selected device")]
-    Mute,
+    Mute {
+        #[structopt(long, default_value = "main", help = "Zone (Main, 2, 3, 4)")]
+        zone: iscp::Zone,
+    },
     #[structopt(about = "Unmutes the selected device")]
-    Unmute,
+    Unmute {
+        #[structopt(long, default_value = "main", help = "Zone (Main, 2, 3, 4)")]
+        zone: iscp::Zone,
+    },
     #[structopt(
         about = "Changes the volume level of the selected device",
         alias = "vol"
@@ -43,15 +87,105 @@ enum Command {
     Volume {
         #[structopt(help = "Volume level (Range from 0 to max)")]
         level: u8,
+        #[structopt(long, default_value = "main", help = "Zone (Main, 2, 3, 4)")]
+        zone: iscp::Zone,
+    },
+    #[structopt(about = "Selects the input source of the selected device")]
+    Input {
+        #[structopt(help = "Input source (e.g. BD/DVD, GAME, PC, TV/CD, FM, NET, BLUETOOTH)")]
+        source: String,
+        #[structopt(long, default_value = "main", help = "Zone (Main, 2, 3, 4)")]
+        zone: iscp::Zone,
+    },
+    #[structopt(
+        about = "Changes the listening mode of the selected device",
+        alias = "lmd"
+    )]
+    Mode {
+        #[structopt(
+            help = "Listening mode (e.g. Stereo, Direct, Pure Audio, or Movie, Music, Game to cycle), lists supported modes if omitted"
+        )]
+        mode: Option<String>,
     },
-    #[structopt(about = "Adjusts the speaker tone of the selected device")]
+    #[structopt(
+        about = "Adjusts the speaker tone of the selected device",
+        setting = structopt::clap::AppSettings::AllowNegativeNumbers
+    )]
     Tone {
-        #[structopt(help = "Speaker (Front)")]
+        #[structopt(
+            help = "Speaker (Front, Center, Surround, Surround Back, Height, Subwoofer, Zone 2, Zone 3)"
+        )]
         speaker: String,
-        #[structopt(help = "Tone (Bass, Treble)")]
-        setting: String,
+        #[structopt(help = "Tone (Bass, Treble), shows the current tone if omitted")]
+        setting: Option<String>,
         #[structopt(help = "Level Shift (Range from -10 to 10)")]
-        level: i8,
+        level: Option<i8>,
+    },
+    #[structopt(
+        about = "Adjusts the temporary channel level of the selected device",
+        setting = structopt::clap::AppSettings::AllowNegativeNumbers
+    )]
+    Level {
+        #[structopt(help = "Channel (Center, Subwoofer, Subwoofer 2)")]
+        channel: String,
+        #[structopt(
+            help = "Level offset (Center from -12 to 12, Subwoofer from -15 to 12) or up/down, shows the current level if omitted"
+        )]
+        level: Option<String>,
+    },
+    #[structopt(about = "Controls the tuner of the selected device")]
+    Tuner {
+        #[structopt(
+            help = "Band (FM, AM) or up/down to tune, shows the current frequency if omitted"
+        )]
+        band: Option<String>,
+        #[structopt(help = "Frequency (FM in MHz, e.g. 101.5, AM in kHz, e.g. 1230)")]
+        frequency: Option<String>,
+    },
+    #[structopt(about = "Shows the RDS information of the selected device")]
+    Rds {
+        #[structopt(help = "Changes the RDS display (Radio Text, Program Type, Traffic Program)")]
+        display: Option<String>,
+        #[structopt(long, help = "Keeps printing RDS updates until interrupted")]
+        watch: bool,
+    },
+    #[structopt(about = "Recalls or stores a tuner preset of the selected device")]
+    Preset {
+        #[structopt(
+            help = "Preset number (Range from 1 to 40) or up/down, shows the current preset if omitted"
+        )]
+        preset: Option<String>,
+        #[structopt(long, help = "Stores the current station as this preset")]
+        store: bool,
+    },
+    #[structopt(
+        about = "Inspects and changes the audio processing features of the selected device"
+    )]
+    Dsp(DspCommand),
+    #[structopt(about = "Starts playback on the network player of the selected device")]
+    Play,
+    #[structopt(about = "Pauses playback on the network player of the selected device")]
+    Pause,
+    #[structopt(about = "Stops playback on the network player of the selected device")]
+    Stop,
+    #[structopt(about = "Skips to the next track on the network player of the selected device")]
+    Next,
+    #[structopt(
+        about = "Skips to the previous track on the network player of the selected device",
+        alias = "previous"
+    )]
+    Prev,
+    #[structopt(about = "Presses a key on the network player of the selected device")]
+    Net {
+        #[structopt(
+            help = "Key (e.g. Play, Stop, Pause, Next, Prev, Repeat, Random, Menu, Return, Up, Down, Select, 0-9)"
+        )]
+        key: String,
+    },
+    #[structopt(about = "Fetches the album art from the network player of the selected device")]
+    Art {
+        #[structopt(short, long, help = "Writes the image to the given file")]
+        out: Option<String>,
     },
     #[structopt(about = "Sends a custom ISCP message")]
     Raw {
@@ -62,63 +196,184 @@ enum Command {
     },
 }
 
+#[derive(StructOpt, PartialEq)]
+enum DspCommand {
+    #[structopt(about = "Lists the audio processing features and their levels")]
+    List,
+    #[structopt(about = "Shows the current level of the audio processing features")]
+    Show {
+        #[structopt(help = "Feature (e.g. Late Night, Dynamic Volume), shows all if omitted")]
+        feature: Option<String>,
+    },
+    #[structopt(about = "Sets the level of an audio processing feature")]
+    Set {
+        #[structopt(
+            help = "Feature (Audyssey, Dynamic EQ, Dynamic Volume, Late Night, Cinema Filter, Music Optimizer, Direct)"
+        )]
+        feature: String,
+        #[structopt(help = "Level (e.g. Off, On, Low, High, Auto)")]
+        level: String,
+    },
+    #[structopt(about = "Switches an audio processing feature to its next level")]
+    Cycle {
+        #[structopt(
+            help = "Feature (Audyssey, Dynamic EQ, Dynamic Volume, Late Night, Cinema Filter, Music Optimizer, Direct)"
+        )]
+        feature: String,
+    },
+}
+
 fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
     let options = ProgramOptions::from_args();
     let mut app = Application::load();
 
     match options.command {
-        Command::Discover => app.discover(),
+        Command::Discover {
+            timeout,
+            limit,
+            quiet,
+            targets,
+            probe,
+        } => {
+            let mut options = iscp::DiscoverOptions {
+                limit,
+                quiet_period: quiet.map(Duration::from_secs),
+                probe_tcp: probe,
+                ..iscp::DiscoverOptions::new(Duration::from_secs(timeout))
+            };
+            if !targets.is_empty() {
+                options.targets = targets;
+            }
+            app.discover(options)
+        }
         Command::List => app.list(),
-        Command::Select { id } => app.select(id),
-        Command::On => match app.device().commands().main().power_on() {
+        Command::Select { id } => app.select(&id),
+        Command::Alias { id, alias } => app.alias(&id, alias.as_deref()),
+        Command::Info => app.info(),
+        Command::Status { now_playing: false } => app.status(),
+        Command::Status { now_playing: true } => app.now_playing(),
+        Command::On { zone } => match app
+            .device()
+            .and_then(|d| d.commands().zone(zone).power_on())
+        {
             Ok(_) => println!("⚡"),
-            Err(_) => println!("Failed to send command"),
+            Err(e) => println!("Failed to send command: {}", e),
         },
-        Command::Off => match app.device().commands().main().power_off() {
+        Command::Off { zone } => match app
+            .device()
+            .and_then(|d| d.commands().zone(zone).power_off())
+        {
             Ok(_) => println!("❌"),
-            Err(_) => println!("Failed to send command"),
+            Err(e) => println!("Failed to send command: {}", e),
         },
-        Command::Mute => match app.device().commands().main().mute() {
+        Command::Mute { zone } => match app.device().and_then(|d| d.commands().zone(zone).mute()) {
             Ok(_) => println!("🔈"),
-            Err(_) => println!("Failed to send command"),
-        },
-        Command::Unmute => match app.device().commands().main().unmute() {
-            Ok(_) => println!("🔊"),
-            Err(_) => println!("Failed to send command"),
+            Err(e) => println!("Failed to send command: {}", e),
         },
-        Command::Volume { level } => {
+        Command::Unmute { zone } => {
+            match app.device().and_then(|d| d.commands().zone(zone).unmute()) {
+                Ok(_) => println!("🔊"),
+                Err(e) => println!("Failed to send command: {}", e),
+            }
+        }
+        Command::Volume { level, zone } => {
             let checked_level = if level > app.volume_max_level {
                 app.volume_max_level
             } else {
                 level
             };
-            match app.device().commands().main().set_volume(checked_level) {
+            match app
+                .device()
+                .and_then(|d| d.commands().zone(zone).set_volume(checked_level))
+            {
                 Ok(_) => println!("🔉"),
-                Err(_) => println!("Failed to send command"),
+                Err(e) => println!("Failed to send command: {}", e),
+            }
+        }
+        Command::Input { source, zone } => match source.parse::<iscp::InputSource>() {
+            Ok(source) => match app
+                .device()
+                .and_then(|d| d.commands().zone(zone).select_input(source))
+            {
+                Ok(_) => println!("🔌"),
+                Err(e) => println!("Failed to send command: {}", e),
+            },
+            Err(_) => {
+                println!("Invalid argument for input, supported inputs:");
+                for source in iscp::InputSource::all() {
+                    println!("\t{}", source);
+                }
+            }
+        },
+        Command::Mode { mode: None } => {
+            println!("Supported listening modes:");
+            for mode in iscp::ListeningMode::all() {
+                println!("\t{}", mode);
+            }
+        }
+        Command::Mode { mode: Some(mode) } => {
+            let result = match mode.to_lowercase().as_str() {
+                "movie" => app
+                    .device()
+                    .and_then(|d| d.commands().main().listening_mode_movie()),
+                "music" => app
+                    .device()
+                    .and_then(|d| d.commands().main().listening_mode_music()),
+                "game" => app
+                    .device()
+                    .and_then(|d| d.commands().main().listening_mode_game()),
+                "up" => app
+                    .device()
+                    .and_then(|d| d.commands().main().listening_mode_up()),
+                "down" => app
+                    .device()
+                    .and_then(|d| d.commands().main().listening_mode_down()),
+                _ => mode.parse().and_then(|mode| {
+                    app.device()
+                        .and_then(|d| d.commands().main().set_listening_mode(mode))
+                }),
+            };
+            match result {
+                Ok(_) => println!("🎭"),
+                Err(iscp::IscpError::InvalidArgument(_)) => {
+                    println!("Invalid argument for mode, run without argument to list modes")
+                }
+                Err(e) => println!("Failed to send command: {}", e),
             }
         }
         Command::Tone {
             speaker,
             setting,
             level,
-        } => match speaker.to_lowercase().as_str() {
-            "front" => match setting.to_lowercase().as_str() {
-                "bass" => match app.device().commands().main().set_tone_front_bass(level) {
-                    Ok(_) => println!("Ok"),
-                    Err(_) => println!("Failed to send command"),
-                },
-                "treble" => match app.device().commands().main().set_tone_front_treble(level) {
-                    Ok(_) => println!("Ok"),
-                    Err(_) => println!("Failed to send command"),
-                },
-                _ => println!("Invalid argument for tone"),
-            },
-            _ => println!("Invalid argument for speaker"),
+        } => app.tone(&speaker, setting.as_deref(), level),
+        Command::Level { channel, level } => app.level(&channel, level.as_deref()),
+        Command::Tuner { band, frequency } => app.tuner(band.as_deref(), frequency.as_deref()),
+        Command::Rds { display, watch } => app.rds(display.as_deref(), watch),
+        Command::Preset { preset, store } => app.preset(preset.as_deref(), store),
+        Command::Dsp(command) => app.dsp(command),
+        Command::Play => app.press(iscp::NetKey::Play, "▶️"),
+        Command::Pause => app.press(iscp::NetKey::Pause, "⏸️"),
+        Command::Stop => app.press(iscp::NetKey::Stop, "⏹️"),
+        Command::Next => app.press(iscp::NetKey::TrackUp, "⏭️"),
+        Command::Prev => app.press(iscp::NetKey::TrackDown, "⏮️"),
+        Command::Art { out } => app.art(out.as_deref()),
+        Command::Net { key } => match key.parse::<iscp::NetKey>() {
+            Ok(key) => app.press(key, "🎵"),
+            Err(_) => {
+                println!("Invalid argument for key, supported keys:");
+                for key in iscp::NetKey::all() {
+                    println!("\t{}", key);
+                }
+            }
         },
         Command::Raw { command, parameter } => {
-            match app.device().raw(command.as_str(), parameter.as_str()) {
+            match app
+                .device()
+                .and_then(|d| d.raw(command.as_str(), parameter.as_str()))
+            {
                 Ok(_) => println!("📡"),
-                Err(_) => println!("Failed to send command"),
+                Err(e) => println!("Failed to send command: {}", e),
             }
         }
     }
@@ -129,7 +384,7 @@ fn main() {
 #[derive(Serialize, Deserialize)]
 struct Application {
     remote: iscp::Remote,
-    selected_device: usize,
+    selected_device: String,
     volume_max_level: u8,
 }
 
@@ -137,7 +392,7 @@ impl Application {
     pub fn new() -> Application {
         Application {
             remote: iscp::Remote::new(),
-            selected_device: 0,
+            selected_device: String::new(),
             volume_max_level: 30,
         }
     }
@@ -146,7 +401,13 @@ impl Application {
         let path = BaseDirs::new().unwrap().config_dir().join(CONFIG_FILE_PATH);
         let file = path.join(CONFIG_FILE_NAME);
         if let Ok(json) = fs::read_to_string(file) {
-            if let Ok(app) = serde_json::from_str(json.as_str()) {
+            let app = serde_json::from_str(json.as_str())
+                .map(migrate_selected_device)
+                .and_then(serde_json::from_value::<Application>);
+            if let Ok(mut app) = app {
+                if let Some(device) = app.remote.device(&app.selected_device) {
+                    app.selected_device = device.identifier();
+                }
                 return app;
             }
         }
@@ -166,35 +427,518 @@ impl Application {
         }
     }
 
-    pub fn discover(&mut self) {
+    pub fn discover(&mut self, options: iscp::DiscoverOptions) {
         println!("Discovering devices...");
-        let timeout = 5;
-        println!("Receive timeout is set to {} seconds", timeout);
-        self.remote.discover(Duration::from_secs(timeout));
+        println!(
+            "Receive timeout is set to {} seconds",
+            options.timeout.as_secs()
+        );
+        let result = self.remote.discover_with(options, |device| {
+            println!("Found {} at {}", device.model, device.address)
+        });
+        if let Err(e) = result {
+            println!("Failed to discover devices: {}", e);
+            return;
+        }
+        if self.remote.device(&self.selected_device).is_none() {
+            if let Some(device) = self.remote.devices.first() {
+                self.selected_device = device.identifier();
+            }
+        }
         self.list();
     }
 
     pub fn list(&self) {
         println!("Available devices:");
         println!();
-        for (index, device) in self.remote.devices.iter().enumerate() {
-            println!("\t{}: {} at {}", index, device.model, device.address);
+        for device in &self.remote.devices {
+            println!(
+                "\t{}: {} at {} (alias {})",
+                device.identifier(),
+                device.model,
+                device.address,
+                device.alias.as_deref().unwrap_or("-")
+            );
+        }
+        println!();
+        match self.remote.device(&self.selected_device) {
+            Some(device) => println!(
+                "Selected device: {}",
+                device.alias.as_deref().unwrap_or(&device.identifier())
+            ),
+            None => println!("Selected device: none"),
+        }
+    }
+
+    pub fn select(&mut self, id: &str) {
+        match self.remote.device(id) {
+            Some(device) => self.selected_device = device.identifier(),
+            None => println!("There is no device with this alias or MAC address"),
+        }
+    }
+
+    pub fn alias(&mut self, id: &str, alias: Option<&str>) {
+        if let Err(e) = self.remote.set_alias(id, alias) {
+            println!("Failed to assign alias: {}", e);
+        }
+    }
+
+    pub fn info(&mut self) {
+        let device = match self.remote.device_mut(&self.selected_device) {
+            Some(device) => device,
+            None => {
+                println!("There is no device selected");
+                return;
+            }
+        };
+        let info = match device.fetch_info() {
+            Ok(info) => info,
+            Err(e) => {
+                println!("Failed to fetch device information: {}", e);
+                return;
+            }
+        };
+        println!(
+            "{} {} (firmware {})",
+            info.brand, info.model, info.firmware_version
+        );
+        println!();
+        println!("Zones:");
+        for zone in &info.zones {
+            println!(
+                "\t{}: {} (max volume {})",
+                zone.zone, zone.name, zone.volume_max
+            );
         }
         println!();
-        println!("Selected device: {}", self.selected_device);
+        println!("Inputs:");
+        for selector in &info.selectors {
+            println!("\t{:02X}: {}", selector.code, selector.name);
+        }
+        if !info.presets.is_empty() {
+            println!();
+            println!("Presets:");
+            for preset in &info.presets {
+                println!(
+                    "\t{}: {} {} {}",
+                    preset.number, preset.band, preset.frequency, preset.name
+                );
+            }
+        }
     }
 
-    pub fn select(&mut self, index: usize) {
-        if index >= self.remote.devices.len() {
-            println!("There is no device with this index");
+    pub fn tone(&self, speaker: &str, setting: Option<&str>, level: Option<i8>) {
+        let speaker = match speaker.parse::<iscp::Speaker>() {
+            Ok(speaker) => speaker,
+            Err(_) => {
+                println!("Invalid argument for speaker, supported speakers:");
+                for speaker in iscp::Speaker::all() {
+                    println!("\t{}", speaker);
+                }
+                return;
+            }
+        };
+        let setting = match setting.map(str::parse::<iscp::ToneSetting>) {
+            Some(Ok(setting)) => setting,
+            Some(Err(_)) => {
+                println!("Invalid argument for tone");
+                return;
+            }
+            None => {
+                match self
+                    .device()
+                    .and_then(|d| d.commands().main().tone(speaker))
+                {
+                    Ok(tone) if speaker.supports(iscp::ToneSetting::Treble) => {
+                        println!("{}: bass {}, treble {}", speaker, tone.bass, tone.treble)
+                    }
+                    Ok(tone) => println!("{}: bass {}", speaker, tone.bass),
+                    Err(e) => println!("Failed to query tone: {}", e),
+                }
+                return;
+            }
+        };
+        let level = match level {
+            Some(level) => level,
+            None => {
+                println!("Missing level for tone");
+                return;
+            }
+        };
+        match self
+            .device()
+            .and_then(|d| d.commands().main().set_tone(speaker, setting, level))
+        {
+            Ok(_) => println!("Ok"),
+            Err(e) => println!("Failed to send command: {}", e),
+        }
+    }
+
+    pub fn level(&self, channel: &str, level: Option<&str>) {
+        let channel = match channel.parse::<iscp::LevelChannel>() {
+            Ok(channel) => channel,
+            Err(_) => {
+                println!("Invalid argument for channel, supported channels:");
+                for channel in iscp::LevelChannel::all() {
+                    println!("\t{}", channel);
+                }
+                return;
+            }
+        };
+        let result = match level.map(str::to_lowercase).as_deref() {
+            None => {
+                match self
+                    .device()
+                    .and_then(|d| d.commands().main().channel_level(channel))
+                {
+                    Ok(level) => println!("{}: {:+}", channel, level),
+                    Err(e) => println!("Failed to query level: {}", e),
+                }
+                return;
+            }
+            Some("up") => self
+                .device()
+                .and_then(|d| d.commands().main().set_channel_level_up(channel)),
+            Some("down") => self
+                .device()
+                .and_then(|d| d.commands().main().set_channel_level_down(channel)),
+            Some(level) => match level.parse::<i8>() {
+                Ok(level) if level >= channel.min_level() && level <= channel.max_level() => self
+                    .device()
+                    .and_then(|d| d.commands().main().set_channel_level(channel, level)),
+                _ => {
+                    println!(
+                        "Invalid argument for level, range from {} to {} or up/down",
+                        channel.min_level(),
+                        channel.max_level()
+                    );
+                    return;
+                }
+            },
+        };
+        match result {
+            Ok(_) => println!("Ok"),
+            Err(e) => println!("Failed to send command: {}", e),
+        }
+    }
+
+    pub fn status(&self) {
+        let device = match self.device() {
+            Ok(device) => device,
+            Err(e) => {
+                println!("Failed to connect: {}", e);
+                return;
+            }
+        };
+        let commands = device.commands();
+        let main = commands.zone(iscp::Zone::Main);
+        match main.power_state() {
+            Ok(state) => println!("Power: {:?}", state),
+            Err(e) => {
+                println!("Failed to query status: {}", e);
+                return;
+            }
+        }
+        if let Ok(volume) = main.volume() {
+            println!("Volume: {}", volume);
+        }
+        if let Ok(muted) = main.is_muted() {
+            println!("Muted: {}", if muted { "yes" } else { "no" });
+        }
+        if let Ok(source) = main.input() {
+            println!("Input: {}", source);
+        }
+        if let Ok(mode) = commands.main().listening_mode() {
+            println!("Listening mode: {}", mode);
+        }
+    }
+
+    pub fn art(&self, out: Option<&str>) {
+        let art = match self.device().and_then(|d| d.commands().net().album_art()) {
+            Ok(art) => art,
+            Err(e) => {
+                println!("Failed to fetch album art: {}", e);
+                return;
+            }
+        };
+        match (art, out) {
+            (iscp::AlbumArt::Link(url), None) => println!("{}", url),
+            (iscp::AlbumArt::Link(url), Some(out)) => {
+                println!("Album art is a link, nothing was written to {}", out);
+                println!("{}", url);
+            }
+            (art, None) => println!("{}", art),
+            (iscp::AlbumArt::Image { data, .. }, Some(out)) => match fs::write(out, data) {
+                Ok(_) => println!("Saved album art to {}", out),
+                Err(e) => println!("Failed to save album art: {}", e),
+            },
+        }
+    }
+
+    pub fn now_playing(&self) {
+        let now_playing = match self.device().and_then(|d| d.commands().net().now_playing()) {
+            Ok(now_playing) => now_playing,
+            Err(e) => {
+                println!("Failed to query now playing: {}", e);
+                return;
+            }
+        };
+        if now_playing == iscp::NowPlaying::default() {
+            println!("Nothing playing");
             return;
         }
-        self.selected_device = index;
+        let time = |duration: Option<Duration>| match duration {
+            Some(duration) => format!("{}:{:02}", duration.as_secs() / 60, duration.as_secs() % 60),
+            None => String::from("--:--"),
+        };
+        if let Some(title) = now_playing.title {
+            println!("Title: {}", title);
+        }
+        if let Some(artist) = now_playing.artist {
+            println!("Artist: {}", artist);
+        }
+        if let Some(album) = now_playing.album {
+            println!("Album: {}", album);
+        }
+        if let (Some(track), Some(track_count)) = (now_playing.track, now_playing.track_count) {
+            println!("Track: {}/{}", track, track_count);
+        }
+        if now_playing.elapsed.is_some() || now_playing.total.is_some() {
+            println!(
+                "Time: {}/{}",
+                time(now_playing.elapsed),
+                time(now_playing.total)
+            );
+        }
+        if let Some(status) = now_playing.status {
+            println!("Status: {}", status);
+        }
+        if let Some(repeat) = now_playing.repeat {
+            println!("Repeat: {}", repeat);
+        }
+        if let Some(shuffle) = now_playing.shuffle {
+            println!("Shuffle: {}", shuffle);
+        }
     }
 
-    pub fn device(&self) -> &iscp::Device {
-        self.remote
-            .device(self.selected_device)
-            .expect("Invalid selected device")
+    pub fn press(&self, key: iscp::NetKey, symbol: &str) {
+        match self.device().and_then(|d| d.commands().net().press(key)) {
+            Ok(_) => println!("{}", symbol),
+            Err(e) => println!("Failed to send command: {}", e),
+        }
+    }
+
+    pub fn tuner(&self, band: Option<&str>, frequency: Option<&str>) {
+        let band = match band.map(str::to_lowercase) {
+            None => {
+                match self.device().and_then(|d| d.commands().tuner().frequency()) {
+                    Ok(frequency) => println!("{}", frequency),
+                    Err(e) => println!("Failed to query frequency: {}", e),
+                }
+                return;
+            }
+            Some(band) => band,
+        };
+        let result = match (band.as_str(), frequency) {
+            ("up", None) => self.device().and_then(|d| d.commands().tuner().tune_up()),
+            ("down", None) => self.device().and_then(|d| d.commands().tuner().tune_down()),
+            (band, None) => band.parse::<iscp::Band>().and_then(|band| {
+                self.device()
+                    .and_then(|d| d.commands().tuner().select_band(band))
+            }),
+            (band, Some(frequency)) => {
+                let frequency = match band.parse::<iscp::Band>() {
+                    Ok(iscp::Band::Fm) => frequency
+                        .parse::<f32>()
+                        .map_err(|_| iscp::IscpError::InvalidArgument(String::from(frequency)))
+                        .and_then(iscp::Frequency::fm),
+                    Ok(iscp::Band::Am) => frequency
+                        .parse::<u32>()
+                        .map_err(|_| iscp::IscpError::InvalidArgument(String::from(frequency)))
+                        .and_then(iscp::Frequency::am),
+                    Err(e) => Err(e),
+                };
+                frequency.and_then(|frequency| {
+                    self.device()
+                        .and_then(|d| d.commands().tuner().tune(frequency))
+                })
+            }
+        };
+        match result {
+            Ok(_) => println!("📻"),
+            Err(iscp::IscpError::InvalidArgument(reason)) => {
+                println!("Invalid argument for tuner: {}", reason)
+            }
+            Err(e) => println!("Failed to send command: {}", e),
+        }
+    }
+
+    pub fn rds(&self, display: Option<&str>, watch: bool) {
+        let device = match self.device() {
+            Ok(device) => device,
+            Err(e) => {
+                println!("Failed to connect: {}", e);
+                return;
+            }
+        };
+        if let Some(display) = display {
+            let result = display
+                .parse::<iscp::RdsDisplay>()
+                .and_then(|display| device.commands().tuner().set_rds_display(display));
+            if let Err(e) = result {
+                println!("Failed to send command: {}", e);
+                return;
+            }
+        }
+        let (sender, updates) = std::sync::mpsc::channel();
+        let result = device
+            .on_rds(move |rds| sender.send(rds).is_ok())
+            .and_then(|_| device.query("SLI"))
+            .and_then(|_| device.query("TUN"))
+            .and_then(|_| device.query("RDS"))
+            .and_then(|_| device.query("FLD"));
+        match result {
+            Ok(_) | Err(iscp::IscpError::NotAvailable(_)) => {}
+            Err(e) => {
+                println!("Failed to query RDS information: {}", e);
+                return;
+            }
+        }
+        match device.rds() {
+            Ok(rds) => println!("{}", rds),
+            Err(e) => println!("Failed to query RDS information: {}", e),
+        }
+        if watch {
+            for rds in updates {
+                println!("{}", rds);
+            }
+        }
+    }
+
+    pub fn preset(&self, preset: Option<&str>, store: bool) {
+        let preset = match preset.map(str::to_lowercase) {
+            None => {
+                match self.device().and_then(|d| d.commands().tuner().preset()) {
+                    Ok(preset) => println!("Preset {}", preset),
+                    Err(e) => println!("Failed to query preset: {}", e),
+                }
+                return;
+            }
+            Some(preset) => preset,
+        };
+        let result = match (preset.as_str(), store) {
+            ("up", false) => self.device().and_then(|d| d.commands().tuner().preset_up()),
+            ("down", false) => self
+                .device()
+                .and_then(|d| d.commands().tuner().preset_down()),
+            (number, store) => match number.parse::<u8>() {
+                Ok(number) if store => self
+                    .device()
+                    .and_then(|d| d.commands().tuner().store_preset(number)),
+                Ok(number) => self
+                    .device()
+                    .and_then(|d| d.commands().tuner().recall_preset(number)),
+                Err(_) => Err(iscp::IscpError::InvalidArgument(format!(
+                    "{} is no preset number",
+                    number
+                ))),
+            },
+        };
+        match result {
+            Ok(_) => println!("📻"),
+            Err(iscp::IscpError::InvalidArgument(reason)) => {
+                println!("Invalid argument for preset: {}", reason)
+            }
+            Err(e) => println!("Failed to send command: {}", e),
+        }
+    }
+
+    pub fn dsp(&self, command: DspCommand) {
+        let feature = match &command {
+            DspCommand::List => {
+                println!("Supported audio processing features:");
+                for feature in iscp::DspFeature::all() {
+                    let levels: Vec<&str> = feature.levels().map(|level| level.name()).collect();
+                    println!("\t{} ({})", feature, levels.join(", "));
+                }
+                return;
+            }
+            DspCommand::Show { feature: None } => {
+                for feature in iscp::DspFeature::all() {
+                    match self.device().and_then(|d| d.commands().main().dsp(feature)) {
+                        Ok(level) => println!("{}: {}", feature, level),
+                        Err(iscp::IscpError::NotAvailable(_)) => {
+                            println!("{}: not available", feature)
+                        }
+                        Err(e) => {
+                            println!("Failed to query {}: {}", feature, e);
+                            return;
+                        }
+                    }
+                }
+                return;
+            }
+            DspCommand::Show {
+                feature: Some(feature),
+            }
+            | DspCommand::Set { feature, .. }
+            | DspCommand::Cycle { feature } => match feature.parse::<iscp::DspFeature>() {
+                Ok(feature) => feature,
+                Err(_) => {
+                    println!("Invalid argument for feature, run dsp list to list features");
+                    return;
+                }
+            },
+        };
+        let result = match &command {
+            DspCommand::Set { level, .. } => match level.parse::<iscp::DspLevel>() {
+                Ok(level) if feature.supports(level) => self
+                    .device()
+                    .and_then(|d| d.commands().main().set_dsp(feature, level)),
+                _ => {
+                    let levels: Vec<&str> = feature.levels().map(|level| level.name()).collect();
+                    println!(
+                        "Invalid argument for level, {} supports {}",
+                        feature,
+                        levels.join(", ")
+                    );
+                    return;
+                }
+            },
+            DspCommand::Cycle { .. } => self
+                .device()
+                .and_then(|d| d.commands().main().cycle_dsp(feature)),
+            _ => {
+                match self.device().and_then(|d| d.commands().main().dsp(feature)) {
+                    Ok(level) => println!("{}: {}", feature, level),
+                    Err(e) => println!("Failed to query {}: {}", feature, e),
+                }
+                return;
+            }
+        };
+        match result {
+            Ok(_) => println!("Ok"),
+            Err(e) => println!("Failed to send command: {}", e),
+        }
+    }
+
+    pub fn device(&self) -> iscp::Result<&iscp::Device> {
+        self.remote.device(&self.selected_device).ok_or_else(|| {
+            iscp::IscpError::Config(String::from("No device selected, run discover first"))
+        })
+    }
+}
+
+// Older configs stored the selected device as its position in the device list
+fn migrate_selected_device(mut config: serde_json::Value) -> serde_json::Value {
+    if let Some(index) = config["selected_device"].as_u64() {
+        let identifier = config["remote"]["devices"]
+            .get(index as usize)
+            .cloned()
+            .and_then(|device| serde_json::from_value::<iscp::Device>(device).ok())
+            .map(|device| device.identifier())
+            .unwrap_or_default();
+        config["selected_device"] = serde_json::Value::String(identifier);
     }
+    config
 }
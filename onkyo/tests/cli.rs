@@ -0,0 +1,65 @@
+use iscp::{Device, MockReceiver, PowerState, Zone};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn receiver() -> MockReceiver {
+    let device = Device::from(
+        String::from("127.0.0.1:0"),
+        String::from("TX-SIM"),
+        String::from("XX"),
+        String::from("0009B0000001"),
+    );
+    MockReceiver::start(&device, None).unwrap()
+}
+
+fn home(name: &str, receiver: &MockReceiver) -> PathBuf {
+    let home = env::temp_dir().join(format!("onkyo-{}-{}", name, std::process::id()));
+    let config = home.join(".config/Havoc/iscp");
+    fs::create_dir_all(&config).unwrap();
+    let json = serde_json::json!({
+        "remote": {
+            "devices": [{
+                "address": receiver.address().to_string(),
+                "model": "TX-SIM",
+                "area": "XX",
+                "mac": "0009B0000001",
+            }],
+        },
+        "selected_device": 0,
+        "volume_max_level": 30,
+    });
+    fs::write(config.join("config.json"), json.to_string()).unwrap();
+    home
+}
+
+fn onkyo(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_onkyo"))
+        .args(args)
+        .env("HOME", home)
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn controls_mock_receiver() {
+    let receiver = receiver();
+    let home = home("controls", &receiver);
+
+    let output = onkyo(&home, &["on"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "⚡\n");
+    onkyo(&home, &["volume", "25"]);
+    onkyo(&home, &["volume", "80", "--zone", "2"]);
+
+    assert_eq!(receiver.power(Zone::Main), Some(PowerState::On));
+    assert_eq!(receiver.volume(Zone::Main), Some(25));
+    assert_eq!(receiver.volume(Zone::Zone2), Some(30));
+
+    let config = fs::read_to_string(home.join(".config/Havoc/iscp/config.json")).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&config).unwrap();
+    assert_eq!(config["selected_device"], "0009B0000001");
+    fs::remove_dir_all(home).unwrap();
+}